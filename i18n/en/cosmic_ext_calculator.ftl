@@ -9,3 +9,259 @@ malformed-expression = Malformed expression
 repository = Repository
 support = Support
 website = Website
+tools = Tools
+edit-expression = Edit expression
+
+## Calculus
+calculus = Calculus
+derivative = Derivative
+integral = Integral
+limit = Limit
+sum = Sum (Σ)
+product = Product (Π)
+operation = Operation
+function = Function
+variable = Variable
+approaches = Approaches
+lower-bound = Lower bound
+upper-bound = Upper bound
+evaluate = Evaluate
+result = Result
+expression = Expression
+symbolic = Symbolic
+numeric = Numeric
+
+## Graph
+graph = Graph
+functions = Functions
+range = Range
+minimum = Minimum
+maximum = Maximum
+plot = Plot
+add-function = Add function
+reset = Reset
+
+## Table of values
+table-of-values = Table of values
+start = Start
+end = End
+step = Step
+generate = Generate
+copy-tsv = Copy as TSV
+export-csv = Export CSV
+copied = Copied to clipboard
+exported = Saved to { $path }
+
+## Regression
+regression = Regression
+model = Model
+linear = Linear
+polynomial = Polynomial
+exponential = Exponential
+logarithmic = Logarithmic
+power = Power
+degree = Degree
+data = Data
+data-placeholder = Paste x and y columns separated by tabs, commas or spaces
+fit = Fit
+equation = Equation
+predict-y = Predict y for x
+
+## Time value of money
+time-value-of-money = Time value of money
+solve-for = Solve for
+payments-at-beginning = Payments at beginning of period
+calculate = Calculate
+payment = Payment
+interest = Interest
+principal = Principal
+balance = Balance
+
+## Settings
+settings = Settings
+keypad = Keypad
+desk-percent = Desk calculator percentages
+desk-percent-description = 200 + 10% gives 220 instead of 200.1
+tax-rates = Tax rates
+active-tax-rate = Active rate
+name = Name
+add = Add
+tax-net = Net: { $value }
+tax-amount = { $name } ({ $rate }%): { $value }
+tax-gross = Gross: { $value }
+
+# Business
+business = Business
+business-description = Enter any two of cost, sell price, margin and markup.
+cost = Cost
+sell-price = Sell price
+margin = Margin %
+markup = Markup %
+insert = Insert
+clear = Clear
+grand-total = Grand total
+grand-total-description = Add every result to a GT register, with GT and CGT keys to recall and clear it.
+grand-total-display = GT { $value }
+
+# Memory
+memory = Memory
+named-memories = Named memories
+store = Store
+store-description = Store saves the result of the current expression under the given name.
+chain-mode = Immediate execution
+chain-mode-description = Apply each operator as it is pressed, so 2 + 3 × 4 = 20 as on basic calculators.
+
+# Paper tape
+paper-tape = Paper tape
+running-total = Running total
+note = Note
+export-text = Export text
+print-to-file = Print to file
+input-mode = Input mode
+input-mode-description = Algebraic input follows operator precedence. RPN uses a stack with ENTER.
+algebraic = Algebraic
+rpn = RPN
+
+# Tip and bill split
+tip-and-split = Tip and bill split
+bill = Bill
+tip-percent = Tip %
+people = People
+round-up = Round up
+round-up-description = Round each share up to the next whole unit.
+tip = Tip
+total = Total
+per-person-label = Per person
+tip-amount = Tip: { $value }
+bill-total = Total: { $value }
+per-person = Per person: { $value }
+
+# Number theory
+number-theory = Number theory
+factorize = Prime factorization
+primality = Primality test
+gcd-lcm = GCD and LCM
+modular-inverse = Modular inverse
+modular-power = Modular exponentiation
+numbers = Numbers
+exponent = Exponent
+modulus = Modulus
+factors = Factors
+unfactored = Unfactored
+composite = Gave up splitting { $value }, a composite too hard to factor
+gcd = GCD
+lcm = LCM
+overflow = Too large
+no-inverse = No inverse exists
+prime = Prime
+probable-prime = Probable prime
+not-prime = Not prime
+composite-divisible = Composite, divisible by { $value }
+
+# Random
+random = Random numbers
+mode = Mode
+random-integer = Integers
+random-float = Decimals
+dice = Dice
+pick = Pick from list
+shuffle = Shuffle list
+count = Count
+items = Items
+items-placeholder = One per line or comma separated
+seed = Seed
+seed-description = Leave empty for a fresh seed. The seed used is kept in the history.
+seed-used = Seed: { $value }
+rolls = Rolls: { $value }
+
+# Number formats
+number-formats = Number formats
+value = Value
+copy = Copy
+roman-numeral = Roman numeral
+in-words = In words
+convert = Convert
+convert-placeholder = Number or Roman numeral
+roman-range = Roman numerals cover whole numbers from 1 to 3999.
+# Number words are assembled from these pieces. Both lists are separated by
+# spaces: the first holds zero to nineteen, the second twenty to ninety.
+# A language without its own pieces falls back to these English ones.
+number-words-ones = zero one two three four five six seven eight nine ten eleven twelve thirteen fourteen fifteen sixteen seventeen eighteen nineteen
+number-words-tens = twenty thirty forty fifty sixty seventy eighty ninety
+number-words-tens-units = { $tens }-{ $units }
+number-words-hundreds = { $units } hundred
+number-words-scale = { $words } { $scale ->
+        [1] thousand
+        [2] million
+        [3] billion
+        [4] trillion
+        [5] quadrillion
+       *[6] quintillion
+    }
+number-words-compound = { $high } { $low }
+number-words-decimal = { $integer } point { $digits }
+number-words-negative = minus { $words }
+
+# Uncertainty
+relative-uncertainty = Relative uncertainty: ±{ $value } %
+
+# Ratio and proportion
+ratio-and-proportion = Ratio and proportion
+proportion = Proportion
+proportion-description = Leave one term empty to solve a : b = c : d for it.
+inverse-proportion = Inverse proportion
+inverse-proportion-description = Solve a · b = c · d, for quantities where one rises as the other falls.
+solve = Solve
+aspect-ratio = Aspect ratio
+width = Width
+height = Height
+ratio = Ratio
+target-width = Target width
+target-height = Target height
+
+# Unit price
+unit-price = Unit price comparison
+price = Price
+quantity-placeholder = Quantity, e.g. 500 g
+compare = Compare
+add-item = Add item
+compare-per = Price per
+compare-per-description = The unit to compare in. Leave empty to use the first item's unit.
+unit-prices = Unit prices
+cheapest = Cheapest
+more-expensive = { $value } % more expensive
+incompatible-unit = Can't be converted to the common unit
+
+# Display
+display = Display
+significant-figures = Significant figures
+significant-figures-description = Round results to a number of significant figures, or for products and quotients to as many as the least precise number typed
+off = Off
+from-inputs = From inputs
+rounded-significant-figures = Rounded to { $count } significant { $count ->
+        [one] figure
+       *[other] figures
+    }
+decimal-places = Decimal places
+decimal-places-description = A fixed number of decimals for amounts, or F for floating. Significant figures take precedence.
+floating = F
+rounding = Rounding
+rounding-description = How results are rounded to the digits shown
+half-up = Half up
+half-even = Half even (banker's)
+truncate = Truncate
+round-away = Round up
+notation = Notation
+notation-description = Click the notation shown with a result to change it for that result only
+normal = Normal
+scientific = Scientific
+engineering = Engineering
+si-prefixes = SI prefixes
+notation-threshold = Scientific from
+notation-threshold-description = Normal notation switches to scientific for results this large, or this small
+digit-grouping = Digit grouping
+digit-grouping-description = Separators between groups of digits in results. Typed numbers can be grouped with thin spaces.
+locale-grouping = Follow locale
+thousands-grouping = Thousands
+indian-grouping = Lakh and crore
+thin-space-grouping = Thin space
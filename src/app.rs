@@ -2,9 +2,15 @@
 
 use std::any::TypeId;
 use std::collections::HashMap;
-use std::process::{Command, Stdio};
 
-use crate::app::{config::CONFIG_VERSION, operations::Calculator, operator::Operator};
+use crate::app::{
+    config::{
+        CONFIG_VERSION, DecimalPlaces, DigitGrouping, InputMode, Memory, Notation, PercentMode,
+        Rounding, SignificantFigures, TaxRate, Threshold,
+    },
+    operations::Calculator,
+    operator::Operator,
+};
 use crate::core::{icons, key_binds::key_binds};
 use crate::fl;
 use cosmic::widget::RcElementWrapper;
@@ -31,10 +37,26 @@ use cosmic::{
     },
 };
 
+mod business;
+mod calculus;
 mod config;
+mod display;
+mod finance;
+mod formats;
+mod number_theory;
 mod operations;
 mod operator;
+mod plot;
+mod random;
+mod ratio;
+mod regression;
+mod rpn;
 pub mod settings;
+mod table;
+mod tape;
+mod tip;
+mod tool;
+mod unit_price;
 
 pub struct CosmicCalculator {
     core: Core,
@@ -49,6 +71,41 @@ pub struct CosmicCalculator {
     toasts: widget::Toasts<Message>,
     input_id: widget::Id,
     button_font_size: f32,
+    tax_rate_labels: Vec<String>,
+    tax_rate_name: String,
+    tax_rate_value: String,
+    memory_name: String,
+    /// Last evaluated operation, such as `+3`, applied again when `=` is
+    /// pressed on an unchanged result.
+    repeat: Option<String>,
+    rpn: rpn::Rpn,
+    input_modes: Vec<String>,
+    significant_figures: Vec<String>,
+    roundings: Vec<String>,
+    decimal_places: Vec<String>,
+    notations: Vec<String>,
+    thresholds: Vec<String>,
+    /// The notation picked for the result on display by clicking it.
+    result_notation: Option<Notation>,
+    digit_groupings: Vec<String>,
+    /// How the system locale groups digits, used by [`DigitGrouping::Locale`].
+    locale_grouping: DigitGrouping,
+    /// Results written into the expression with digit grouping, which is
+    /// taken out again before evaluation. Typed commas are left alone.
+    grouped_results: Vec<String>,
+    tape: tape::Tape,
+    business: business::Business,
+    calculus: calculus::Calculus,
+    plot: plot::Plot,
+    table: table::Table,
+    regression: regression::Regression,
+    finance: finance::Finance,
+    tip: tip::Tip,
+    number_theory: number_theory::NumberTheory,
+    random: random::Random,
+    formats: formats::Formats,
+    ratio: ratio::Ratio,
+    unit_price: unit_price::UnitPrice,
 }
 
 #[derive(Debug, Clone)]
@@ -67,15 +124,63 @@ pub enum Message {
     CloseToast(ToastId),
     Open(String),
     SetDecimalComma(bool),
+    SetLocaleGrouping(DigitGrouping),
+    SetPercentMode(PercentMode),
+    SetInputMode(InputMode),
+    TaxRateName(String),
+    TaxRateValue(String),
+    AddTaxRate,
+    RemoveTaxRate(usize),
+    SelectTaxRate(usize),
+    SetGrandTotalEnabled(bool),
+    SetSignificantFigures(SignificantFigures),
+    SetRounding(Rounding),
+    SetDecimalPlaces(DecimalPlaces),
+    SetNotation(Notation),
+    SetNotationThreshold(Threshold),
+    SetDigitGrouping(DigitGrouping),
+    ToggleNotation,
+    MemoryName(String),
+    StoreMemory,
+    InsertMemory(usize),
+    RemoveMemory(usize),
     Evaluate,
     Window,
     Resized(cosmic::iced::Size),
+    Tape(tape::Message),
+    Business(business::Message),
+    Calculus(calculus::Message),
+    Plot(plot::Message),
+    Table(table::Message),
+    Regression(regression::Message),
+    Finance(finance::Message),
+    Tip(tip::Message),
+    NumberTheory(number_theory::Message),
+    Random(random::Message),
+    Formats(formats::Message),
+    Ratio(ratio::Message),
+    UnitPrice(unit_price::Message),
 }
 
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
 pub enum ContextPage {
     #[default]
     About,
+    Settings,
+    Memory,
+    Tape,
+    Business,
+    Calculus,
+    Plot,
+    Table,
+    Regression,
+    Finance,
+    Tip,
+    NumberTheory,
+    Random,
+    Formats,
+    Ratio,
+    UnitPrice,
 }
 
 #[derive(Clone, Debug)]
@@ -87,7 +192,22 @@ pub struct Flags {
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum MenuAction {
     About,
+    Settings,
     ClearHistory,
+    Memory,
+    Tape,
+    Business,
+    Calculus,
+    Plot,
+    Table,
+    Regression,
+    Finance,
+    Tip,
+    NumberTheory,
+    Random,
+    Formats,
+    Ratio,
+    UnitPrice,
 }
 
 impl menu::action::MenuAction for MenuAction {
@@ -96,13 +216,29 @@ impl menu::action::MenuAction for MenuAction {
     fn message(&self) -> Self::Message {
         match self {
             MenuAction::About => Message::ToggleContextPage(ContextPage::About),
+            MenuAction::Settings => Message::ToggleContextPage(ContextPage::Settings),
             MenuAction::ClearHistory => Message::CleanHistory,
+            MenuAction::Memory => Message::ToggleContextPage(ContextPage::Memory),
+            MenuAction::Tape => Message::ToggleContextPage(ContextPage::Tape),
+            MenuAction::Business => Message::ToggleContextPage(ContextPage::Business),
+            MenuAction::Calculus => Message::ToggleContextPage(ContextPage::Calculus),
+            MenuAction::Plot => Message::ToggleContextPage(ContextPage::Plot),
+            MenuAction::Table => Message::ToggleContextPage(ContextPage::Table),
+            MenuAction::Regression => Message::ToggleContextPage(ContextPage::Regression),
+            MenuAction::Finance => Message::ToggleContextPage(ContextPage::Finance),
+            MenuAction::Tip => Message::ToggleContextPage(ContextPage::Tip),
+            MenuAction::NumberTheory => Message::ToggleContextPage(ContextPage::NumberTheory),
+            MenuAction::Random => Message::ToggleContextPage(ContextPage::Random),
+            MenuAction::Formats => Message::ToggleContextPage(ContextPage::Formats),
+            MenuAction::Ratio => Message::ToggleContextPage(ContextPage::Ratio),
+            MenuAction::UnitPrice => Message::ToggleContextPage(ContextPage::UnitPrice),
         }
     }
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum NavMenuAction {
+    Edit(nav_bar::Id),
     Delete(nav_bar::Id),
 }
 
@@ -173,13 +309,17 @@ impl Application for CosmicCalculator {
 
     fn on_nav_select(&mut self, id: nav_bar::Id) -> Task<Self::Message> {
         self.nav.activate(id);
-        self.nav
-            .active_data()
-            .map_or(Task::none(), |data: &Calculator| {
-                self.calculator.expression = data.outcome.to_string();
-                self.calculator.outcome = String::new();
-                Task::none()
-            })
+        let Some(data) = self.nav.active_data::<Calculator>().cloned() else {
+            return Task::none();
+        };
+        // Reused results follow the current rounding settings, applied to the
+        // digits qalc computed rather than to the ones shown.
+        let unrounded = data.unrounded.as_deref().unwrap_or(&data.outcome);
+        self.show_result(self.round_outcome(unrounded, &data.expression).0);
+        self.calculator.outcome = String::new();
+        self.calculator.unrounded = None;
+        self.calculator.details = data.details;
+        Task::none()
     }
 
     fn init(core: Core, flags: Self::Flags) -> (Self, Task<Self::Message>) {
@@ -222,8 +362,74 @@ impl Application for CosmicCalculator {
             toasts: widget::toaster::Toasts::new(Message::CloseToast),
             input_id: widget::Id::unique(),
             button_font_size: 20.0,
+            tax_rate_labels: vec![],
+            tax_rate_name: String::new(),
+            tax_rate_value: String::new(),
+            memory_name: String::new(),
+            repeat: None,
+            rpn: rpn::Rpn {
+                stack: flags.config.rpn_stack.clone(),
+                last_x: None,
+            },
+            input_modes: vec![fl!("algebraic"), fl!("chain-mode"), fl!("rpn")],
+            significant_figures: SignificantFigures::ALL
+                .iter()
+                .map(|figures| match figures {
+                    SignificantFigures::Off => fl!("off"),
+                    SignificantFigures::Inferred => fl!("from-inputs"),
+                    SignificantFigures::Fixed(figures) => figures.to_string(),
+                })
+                .collect(),
+            roundings: vec![
+                fl!("half-up"),
+                fl!("half-even"),
+                fl!("truncate"),
+                fl!("round-away"),
+            ],
+            decimal_places: DecimalPlaces::ALL
+                .iter()
+                .map(|places| match places {
+                    DecimalPlaces::Floating => fl!("floating"),
+                    DecimalPlaces::Fixed(places) => places.to_string(),
+                })
+                .collect(),
+            notations: vec![
+                fl!("normal"),
+                fl!("scientific"),
+                fl!("engineering"),
+                fl!("si-prefixes"),
+            ],
+            thresholds: Threshold::ALL
+                .iter()
+                .map(|threshold| format!("10{}", display::superscript(threshold.0 as i32)))
+                .collect(),
+            result_notation: None,
+            digit_groupings: vec![
+                fl!("off"),
+                fl!("locale-grouping"),
+                fl!("thousands-grouping"),
+                fl!("indian-grouping"),
+                fl!("thin-space-grouping"),
+            ],
+            locale_grouping: DigitGrouping::Thousands,
+            grouped_results: vec![],
+            tape: tape::Tape::default(),
+            business: business::Business::default(),
+            calculus: calculus::Calculus::default(),
+            plot: plot::Plot::default(),
+            table: table::Table::default(),
+            regression: regression::Regression::default(),
+            finance: finance::Finance::default(),
+            tip: tip::Tip::default(),
+            number_theory: number_theory::NumberTheory::default(),
+            random: random::Random::default(),
+            formats: formats::Formats::default(),
+            ratio: ratio::Ratio::default(),
+            unit_price: unit_price::UnitPrice::default(),
         };
 
+        app.refresh_tax_rate_labels();
+
         let mut tasks = vec![];
 
         tasks.push(app.set_window_title(fl!("app-title"), app.core.main_window_id().unwrap()));
@@ -232,29 +438,58 @@ impl Application for CosmicCalculator {
             async move { operations::uses_decimal_comma().await },
             |decimal_comma| cosmic::Action::App(Message::SetDecimalComma(decimal_comma)),
         ));
+        tasks.push(Task::perform(
+            async move { operations::locale_grouping().await },
+            |grouping| cosmic::Action::App(Message::SetLocaleGrouping(grouping)),
+        ));
 
         (app, Task::batch(tasks))
     }
 
     fn header_start<'a>(&'a self) -> Vec<Element<'a, Self::Message>> {
-        let menu_bar = menu::bar(vec![menu::Tree::with_children(
-            RcElementWrapper::new(menu::root(fl!("view")).into()),
-            menu::items(
-                &self.key_binds,
-                vec![
-                    menu::Item::Button(
-                        fl!("clear-history"),
-                        Some(icons::get_handle("large-brush-symbolic", 14)),
-                        MenuAction::ClearHistory,
-                    ),
-                    menu::Item::Button(
-                        fl!("about"),
-                        Some(icons::get_handle("settings-symbolic", 14)),
-                        MenuAction::About,
-                    ),
-                ],
+        let menu_bar = menu::bar(vec![
+            menu::Tree::with_children(
+                RcElementWrapper::new(menu::root(fl!("view")).into()),
+                menu::items(
+                    &self.key_binds,
+                    vec![
+                        menu::Item::Button(
+                            fl!("clear-history"),
+                            Some(icons::get_handle("large-brush-symbolic", 14)),
+                            MenuAction::ClearHistory,
+                        ),
+                        menu::Item::Button(fl!("memory"), None, MenuAction::Memory),
+                        menu::Item::Button(fl!("paper-tape"), None, MenuAction::Tape),
+                        menu::Item::Button(fl!("number-formats"), None, MenuAction::Formats),
+                        menu::Item::Button(fl!("settings"), None, MenuAction::Settings),
+                        menu::Item::Button(
+                            fl!("about"),
+                            Some(icons::get_handle("settings-symbolic", 14)),
+                            MenuAction::About,
+                        ),
+                    ],
+                ),
+            ),
+            menu::Tree::with_children(
+                RcElementWrapper::new(menu::root(fl!("tools")).into()),
+                menu::items(
+                    &self.key_binds,
+                    vec![
+                        menu::Item::Button(fl!("business"), None, MenuAction::Business),
+                        menu::Item::Button(fl!("calculus"), None, MenuAction::Calculus),
+                        menu::Item::Button(fl!("graph"), None, MenuAction::Plot),
+                        menu::Item::Button(fl!("table-of-values"), None, MenuAction::Table),
+                        menu::Item::Button(fl!("regression"), None, MenuAction::Regression),
+                        menu::Item::Button(fl!("time-value-of-money"), None, MenuAction::Finance),
+                        menu::Item::Button(fl!("tip-and-split"), None, MenuAction::Tip),
+                        menu::Item::Button(fl!("number-theory"), None, MenuAction::NumberTheory),
+                        menu::Item::Button(fl!("random"), None, MenuAction::Random),
+                        menu::Item::Button(fl!("ratio-and-proportion"), None, MenuAction::Ratio),
+                        menu::Item::Button(fl!("unit-price"), None, MenuAction::UnitPrice),
+                    ],
+                ),
             ),
-        )])
+        ])
         .item_height(ItemHeight::Dynamic(40))
         .item_width(ItemWidth::Uniform(240))
         .spacing(4.0);
@@ -262,9 +497,22 @@ impl Application for CosmicCalculator {
         vec![menu_bar.into()]
     }
 
+    fn header_end<'a>(&'a self) -> Vec<Element<'a, Self::Message>> {
+        vec![
+            widget::button::text(fl!("business"))
+                .on_press(Message::ToggleContextPage(ContextPage::Business))
+                .into(),
+        ]
+    }
+
     fn nav_context_menu(&self) -> Option<Vec<menu::Tree<cosmic::Action<Self::Message>>>> {
         let items = self.nav.iter().map(|entity| {
-            let mut items: Vec<widget::menu::Item<NavMenuAction, String>> = Vec::with_capacity(1);
+            let mut items: Vec<widget::menu::Item<NavMenuAction, String>> = Vec::with_capacity(2);
+            items.push(cosmic::widget::menu::Item::Button(
+                fl!("edit-expression"),
+                None,
+                NavMenuAction::Edit(entity),
+            ));
             items.push(cosmic::widget::menu::Item::Button(
                 fl!("delete"),
                 Some(icons::get_handle("user-trash-symbolic", 14)),
@@ -282,133 +530,235 @@ impl Application for CosmicCalculator {
     fn view<'a>(&'a self) -> Element<'a, Self::Message> {
         let spacing = cosmic::theme::active().cosmic().spacing;
 
-        widget::column::with_capacity(2)
+        let mut display = widget::column::with_capacity(3);
+        if self.config.grand_total_enabled {
+            display = display.push(
+                widget::text::caption(fl!(
+                    "grand-total-display",
+                    value = self.shown(&operations::number_literal(
+                        self.config.grand_total,
+                        self.calculator.decimal_comma
+                    ))
+                ))
+                .width(Length::Fill)
+                .align_x(Alignment::End),
+            );
+        }
+        let rpn = self.config.input_mode == InputMode::Rpn;
+        if rpn {
+            for (label, value) in self.rpn.levels() {
+                display = display.push(
+                    widget::row::with_capacity(2)
+                        .push(widget::text::caption(label).width(Length::Fixed(32.0)))
+                        .push(
+                            widget::text::title4(self.shown(value))
+                                .width(Length::Fill)
+                                .align_x(Alignment::End),
+                        ),
+                );
+            }
+        }
+        // Clicking the notation shown with a result cycles it, like the SCI
+        // and ENG keys of a scientific calculator.
+        if !self.calculator.outcome.is_empty()
+            && self.calculator.expression == self.calculator.outcome
+            && display::Decimal::parse(&self.calculator.outcome, self.calculator.decimal_comma)
+                .is_some()
+        {
+            let symbol = match self.result_notation.unwrap_or(self.config.notation) {
+                Notation::Normal => "NORM",
+                Notation::Scientific => "SCI",
+                Notation::Engineering => "ENG",
+                Notation::Si => "SI",
+            };
+            display = display.push(
+                widget::container(widget::button::text(symbol).on_press(Message::ToggleNotation))
+                    .width(Length::Fill)
+                    .align_x(Alignment::End),
+            );
+        }
+        display = display.push(
+            widget::text_input("", &self.calculator.expression)
+                .on_input(Message::Input)
+                .on_submit(|_| Message::Operator(Operator::Equal))
+                .id(self.input_id.clone())
+                .size(32.0)
+                .width(Length::Fill),
+        );
+        if !self.calculator.details.is_empty() {
+            display = display.push(
+                widget::text::caption(self.calculator.details.join("\n")).width(Length::Fill),
+            );
+        }
+
+        // Optional desk keys share one row above the standard keypad.
+        let mut registers = Vec::with_capacity(4);
+        if !self.config.tax_rates.is_empty() {
+            registers.push(Operator::TaxPlus);
+            registers.push(Operator::TaxMinus);
+        }
+        if self.config.grand_total_enabled {
+            registers.push(Operator::GrandTotal);
+            registers.push(Operator::ClearGrandTotal);
+        }
+
+        let mut keypad = widget::column::with_capacity(8).push(
+            widget::row::with_children(
+                [
+                    Operator::MemoryClear,
+                    Operator::MemoryRecall,
+                    Operator::MemorySubtract,
+                    Operator::MemoryAdd,
+                ]
+                .into_iter()
+                .map(|operator| self.button(Message::Operator(operator), theme::Button::Standard))
+                .collect(),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .spacing(spacing.space_xs),
+        );
+        if !registers.is_empty() {
+            keypad = keypad.push(
+                widget::row::with_children(
+                    registers
+                        .into_iter()
+                        .map(|operator| {
+                            self.button(Message::Operator(operator), theme::Button::Standard)
+                        })
+                        .collect(),
+                )
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .spacing(spacing.space_xs),
+            );
+        }
+        // RPN has no use for parentheses, so its stack keys take their place.
+        let second_row = if rpn {
+            vec![
+                Operator::Swap,
+                Operator::Roll,
+                Operator::Drop,
+                Operator::LastX,
+            ]
+        } else {
+            vec![
+                Operator::ParenthesesOpen,
+                Operator::ParenthesesClose,
+                Operator::SquareRoot,
+            ]
+        };
+        let keypad = keypad
             .push(
-                widget::text_input("", &self.calculator.expression)
-                    .on_input(Message::Input)
-                    .on_submit(|_| Message::Operator(Operator::Equal))
-                    .id(self.input_id.clone())
-                    .size(32.0)
-                    .width(Length::Fill),
+                widget::row::with_capacity(5)
+                    .push(self.button(
+                        Message::Operator(Operator::Clear),
+                        theme::Button::Destructive,
+                    ))
+                    .push(self.button(Message::Operator(Operator::Negate), theme::Button::Standard))
+                    .push(self.button(
+                        Message::Operator(Operator::Percent),
+                        theme::Button::Standard,
+                    ))
+                    .push(self.button(
+                        Message::Operator(if rpn {
+                            Operator::SquareRoot
+                        } else {
+                            Operator::Modulus
+                        }),
+                        theme::Button::Standard,
+                    ))
+                    .push(self.button(Message::Operator(Operator::Power), theme::Button::Suggested))
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .spacing(spacing.space_xs),
             )
             .push(
-                widget::column::with_capacity(6)
-                    .push(
-                        widget::row::with_capacity(4)
-                            .push(self.button(
-                                Message::Operator(Operator::Clear),
-                                theme::Button::Destructive,
-                            ))
-                            .push(self.button(
-                                Message::Operator(Operator::Negate),
-                                theme::Button::Standard,
-                            ))
-                            .push(self.button(
-                                Message::Operator(Operator::Modulus),
-                                theme::Button::Standard,
-                            ))
-                            .push(self.button(
-                                Message::Operator(Operator::Power),
-                                theme::Button::Suggested,
-                            ))
-                            .width(Length::Fill)
-                            .height(Length::Fill)
-                            .spacing(spacing.space_xs),
-                    )
-                    .push(
-                        widget::row::with_capacity(4)
-                            .push(self.button(
-                                Message::Operator(Operator::ParenthesesOpen),
-                                theme::Button::Standard,
-                            ))
-                            .push(self.button(
-                                Message::Operator(Operator::ParenthesesClose),
-                                theme::Button::Standard,
-                            ))
-                            .push(self.button(
-                                Message::Operator(Operator::SquareRoot),
-                                theme::Button::Standard,
-                            ))
-                            .push(self.button(
-                                Message::Operator(Operator::Divide),
-                                theme::Button::Suggested,
-                            ))
-                            .width(Length::Fill)
-                            .height(Length::Fill)
-                            .spacing(spacing.space_xs),
-                    )
-                    .push(
-                        widget::row::with_capacity(4)
-                            .push(self.button(Message::Number(7.0), theme::Button::Text))
-                            .push(self.button(Message::Number(8.0), theme::Button::Text))
-                            .push(self.button(Message::Number(9.0), theme::Button::Text))
-                            .push(self.button(
-                                Message::Operator(Operator::Multiply),
-                                theme::Button::Suggested,
-                            ))
-                            .width(Length::Fill)
-                            .height(Length::Fill)
-                            .spacing(spacing.space_xs),
-                    )
-                    .push(
-                        widget::row::with_capacity(4)
-                            .push(self.button(Message::Number(4.0), theme::Button::Text))
-                            .push(self.button(Message::Number(5.0), theme::Button::Text))
-                            .push(self.button(Message::Number(6.0), theme::Button::Text))
-                            .push(self.button(
-                                Message::Operator(Operator::Subtract),
-                                theme::Button::Suggested,
-                            ))
-                            .width(Length::Fill)
-                            .height(Length::Fill)
-                            .spacing(spacing.space_xs),
-                    )
-                    .push(
-                        widget::row::with_capacity(4)
-                            .push(self.button(Message::Number(1.0), theme::Button::Text))
-                            .push(self.button(Message::Number(2.0), theme::Button::Text))
-                            .push(self.button(Message::Number(3.0), theme::Button::Text))
-                            .push(
-                                self.button(
-                                    Message::Operator(Operator::Add),
-                                    theme::Button::Suggested,
-                                ),
-                            )
-                            .width(Length::Fill)
-                            .height(Length::Fill)
-                            .spacing(spacing.space_xs),
-                    )
-                    .push(
-                        widget::row::with_capacity(4)
-                            .push(self.button(Message::Number(0.0), theme::Button::Text))
-                            .push(
-                                self.button(
-                                    Message::Operator(Operator::Point),
-                                    theme::Button::Text,
-                                ),
-                            )
-                            .push(self.button(
-                                Message::Operator(Operator::Backspace),
-                                theme::Button::Destructive,
-                            ))
-                            .push(self.button(
-                                Message::Operator(Operator::Equal),
-                                theme::Button::Suggested,
-                            ))
-                            .width(Length::Fill)
-                            .height(Length::Fill)
-                            .spacing(spacing.space_xs),
-                    )
-                    .push(widget::row(vec![widget::toaster(
-                        &self.toasts,
-                        widget::space::horizontal(),
-                    )]))
-                    .max_width(1000.0)
+                widget::row::with_children(
+                    second_row
+                        .into_iter()
+                        .map(|operator| {
+                            self.button(Message::Operator(operator), theme::Button::Standard)
+                        })
+                        .chain(std::iter::once(self.button(
+                            Message::Operator(Operator::Divide),
+                            theme::Button::Suggested,
+                        )))
+                        .collect(),
+                )
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .spacing(spacing.space_xs),
+            )
+            .push(
+                widget::row::with_capacity(4)
+                    .push(self.button(Message::Number(7.0), theme::Button::Text))
+                    .push(self.button(Message::Number(8.0), theme::Button::Text))
+                    .push(self.button(Message::Number(9.0), theme::Button::Text))
+                    .push(self.button(
+                        Message::Operator(Operator::Multiply),
+                        theme::Button::Suggested,
+                    ))
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .spacing(spacing.space_xs),
+            )
+            .push(
+                widget::row::with_capacity(4)
+                    .push(self.button(Message::Number(4.0), theme::Button::Text))
+                    .push(self.button(Message::Number(5.0), theme::Button::Text))
+                    .push(self.button(Message::Number(6.0), theme::Button::Text))
+                    .push(self.button(
+                        Message::Operator(Operator::Subtract),
+                        theme::Button::Suggested,
+                    ))
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .spacing(spacing.space_xs),
+            )
+            .push(
+                widget::row::with_capacity(4)
+                    .push(self.button(Message::Number(1.0), theme::Button::Text))
+                    .push(self.button(Message::Number(2.0), theme::Button::Text))
+                    .push(self.button(Message::Number(3.0), theme::Button::Text))
+                    .push(self.button(Message::Operator(Operator::Add), theme::Button::Suggested))
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .spacing(spacing.space_xs),
+            )
+            .push(
+                widget::row::with_capacity(4)
+                    .push(self.button(Message::Number(0.0), theme::Button::Text))
+                    .push(self.button(Message::Operator(Operator::Point), theme::Button::Text))
+                    .push(self.button(
+                        Message::Operator(Operator::Backspace),
+                        theme::Button::Destructive,
+                    ))
+                    .push(self.button(
+                        Message::Operator(if rpn {
+                            Operator::Enter
+                        } else {
+                            Operator::Equal
+                        }),
+                        theme::Button::Suggested,
+                    ))
                     .width(Length::Fill)
                     .height(Length::Fill)
-                    .align_x(Alignment::Center)
                     .spacing(spacing.space_xs),
             )
+            .push(widget::row(vec![widget::toaster(
+                &self.toasts,
+                widget::space::horizontal(),
+            )]))
+            .max_width(1000.0)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .align_x(Alignment::Center)
+            .spacing(spacing.space_xs);
+
+        widget::column::with_capacity(2)
+            .push(display)
+            .push(keypad)
             .align_x(Alignment::Center)
             .spacing(spacing.space_s)
             .padding(spacing.space_xxs)
@@ -444,93 +794,266 @@ impl Application for CosmicCalculator {
             }
             Message::SetDecimalComma(decimal_comma) => {
                 self.calculator.decimal_comma = decimal_comma;
+                self.refresh_tax_rate_labels();
                 tracing::info!("Calculator initialized");
             }
-            Message::Number(num) => self.calculator.on_number_press(num),
-            Message::Input(input) => self.calculator.on_input(input),
-            Message::Operator(operator) => {
-                if let Some(operations::Message::Evaluate) =
-                    self.calculator.on_operator_press(&operator)
+            Message::SetLocaleGrouping(grouping) => self.locale_grouping = grouping,
+            Message::SetPercentMode(percent_mode) => {
+                if let Some(config_handler) = &self.config_handler
+                    && let Err(err) = self.config.set_percent_mode(config_handler, percent_mode)
                 {
-                    tasks.push(self.update(Message::Evaluate));
+                    tasks.push(self.save_settings_failed(err));
                 }
             }
-            Message::Evaluate => {
-                // An empty expression drops qalc into interactive mode, which hangs.
-                if self.calculator.expression.trim().is_empty() {
-                    return Task::batch(tasks);
+            Message::SetInputMode(input_mode) => {
+                if let Some(config_handler) = &self.config_handler
+                    && let Err(err) = self.config.set_input_mode(config_handler, input_mode)
+                {
+                    tasks.push(self.save_settings_failed(err));
                 }
-
-                let mut command = Command::new("qalc");
-                // Never let qalc block waiting on stdin.
-                command.stdin(Stdio::null());
-                command.args(["-t"]);
-                command.args(["-u8"]);
-                command.args(["-set", "maxdeci 9"]);
-
-                if self.calculator.decimal_comma {
-                    command.args(["-set", "decimal comma on"]);
-                } else {
-                    command.args(["-set", "decimal comma off"]);
+            }
+            Message::SetGrandTotalEnabled(enabled) => {
+                if let Some(config_handler) = &self.config_handler
+                    && let Err(err) = self.config.set_grand_total_enabled(config_handler, enabled)
+                {
+                    tasks.push(self.save_settings_failed(err));
                 }
-
-                if operations::autocalc() {
-                    command.args(["-set", "autocalc off"]);
+            }
+            Message::SetSignificantFigures(figures) => {
+                if let Some(config_handler) = &self.config_handler
+                    && let Err(err) = self.config.set_significant_figures(config_handler, figures)
+                {
+                    tasks.push(self.save_settings_failed(err));
                 }
-
-                command.args([&self.calculator.expression.trim().to_string()]);
-
-                let Ok(output) = command.env("LANG", "C").output() else {
-                    tracing::error!("Failed to execute qalc command");
-                    tasks.push(self.update(Message::ShowToast(
-                        "Failed to execute qalc command".to_string(),
-                    )));
-                    return Task::batch(tasks);
+            }
+            Message::SetRounding(rounding) => {
+                if let Some(config_handler) = &self.config_handler
+                    && let Err(err) = self.config.set_rounding(config_handler, rounding)
+                {
+                    tasks.push(self.save_settings_failed(err));
+                }
+            }
+            Message::SetDecimalPlaces(places) => {
+                if let Some(config_handler) = &self.config_handler
+                    && let Err(err) = self.config.set_decimal_places(config_handler, places)
+                {
+                    tasks.push(self.save_settings_failed(err));
+                }
+            }
+            Message::SetNotation(notation) => {
+                if let Some(config_handler) = &self.config_handler
+                    && let Err(err) = self.config.set_notation(config_handler, notation)
+                {
+                    tasks.push(self.save_settings_failed(err));
+                }
+            }
+            Message::SetNotationThreshold(threshold) => {
+                if let Some(config_handler) = &self.config_handler
+                    && let Err(err) = self
+                        .config
+                        .set_notation_threshold(config_handler, threshold)
+                {
+                    tasks.push(self.save_settings_failed(err));
+                }
+            }
+            Message::SetDigitGrouping(grouping) => {
+                if let Some(config_handler) = &self.config_handler
+                    && let Err(err) = self.config.set_digit_grouping(config_handler, grouping)
+                {
+                    tasks.push(self.save_settings_failed(err));
+                }
+            }
+            Message::ToggleNotation => {
+                if let Some(decimal) =
+                    display::Decimal::parse(&self.calculator.outcome, self.calculator.decimal_comma)
+                {
+                    let notation = self.result_notation.unwrap_or(self.config.notation).next();
+                    self.result_notation = Some(notation);
+                    // Picked by hand, normal notation writes out every digit.
+                    let outcome = decimal.format(&display::Style {
+                        notation,
+                        threshold: u32::MAX,
+                        ..self.style()
+                    });
+                    self.show_result(outcome.clone());
+                    self.calculator.outcome = outcome;
+                }
+            }
+            Message::MemoryName(name) => self.memory_name = name,
+            Message::StoreMemory => {
+                let name = self.memory_name.trim().to_string();
+                if name.is_empty() {
+                    return self.evaluate_failed(operations::EvaluateError::InvalidInput);
+                }
+                let value = match self.current_value() {
+                    Ok(value) => value,
+                    Err(err) => return self.evaluate_failed(err),
                 };
-
-                let outcome = String::from_utf8(output.stdout)
-                    .unwrap_or_default()
-                    .replace(['\n', '\r'], "")
-                    // Strip any stray interactive-prompt artifact.
-                    .replace("> ", "")
-                    .trim()
-                    .to_string();
-
-                // qalc writes warnings to stderr even on success, so only treat the
-                // run as failed when stdout has no usable result.
-                if outcome.is_empty() {
-                    let error = String::from_utf8(output.stderr).unwrap_or_default();
-                    if error.is_empty() {
-                        tracing::error!("Failed to parse qalc output");
-                        tasks.push(self.update(Message::ShowToast(
-                            "Failed to parse qalc output".to_string(),
-                        )));
-                    } else {
-                        tracing::error!("An error occurred: {}", error);
-                        tasks
-                            .push(self.update(Message::ShowToast("An error occurred".to_string())));
+                let mut memories = self.config.memories.clone();
+                match memories.iter_mut().find(|memory| memory.name == name) {
+                    Some(memory) => memory.value = value,
+                    None => memories.push(Memory { name, value }),
+                }
+                tasks.push(self.set_memories(memories));
+                self.memory_name.clear();
+            }
+            Message::InsertMemory(index) => {
+                if let Some(memory) = self.config.memories.get(index).cloned() {
+                    self.insert_value(&memory.value);
+                    tasks.push(widget::text_input::focus(self.input_id.clone()));
+                }
+            }
+            Message::RemoveMemory(index) => {
+                let mut memories = self.config.memories.clone();
+                if index < memories.len() {
+                    memories.remove(index);
+                    tasks.push(self.set_memories(memories));
+                }
+            }
+            Message::TaxRateName(name) => self.tax_rate_name = name,
+            Message::TaxRateValue(value) => self.tax_rate_value = value,
+            Message::AddTaxRate => {
+                let name = self.tax_rate_name.trim().to_string();
+                let Some(rate) =
+                    operations::parse_number(&self.tax_rate_value, self.calculator.decimal_comma)
+                else {
+                    return self.evaluate_failed(operations::EvaluateError::InvalidInput);
+                };
+                if name.is_empty() {
+                    return self.evaluate_failed(operations::EvaluateError::InvalidInput);
+                }
+                let mut tax_rates = self.config.tax_rates.clone();
+                tax_rates.push(TaxRate { name, rate });
+                tasks.push(self.set_tax_rates(tax_rates));
+                self.tax_rate_name.clear();
+                self.tax_rate_value.clear();
+            }
+            Message::RemoveTaxRate(index) => {
+                let mut tax_rates = self.config.tax_rates.clone();
+                if index < tax_rates.len() {
+                    tax_rates.remove(index);
+                    // Keep TAX+ and TAX− on the same rate, or the one that
+                    // took the removed rate's place.
+                    let mut selected = self.config.selected_tax_rate;
+                    if index < selected {
+                        selected -= 1;
                     }
-                    return Task::batch(tasks);
+                    let selected = selected.min(tax_rates.len().saturating_sub(1));
+                    tasks.push(self.set_tax_rates(tax_rates));
+                    if selected != self.config.selected_tax_rate {
+                        tasks.push(self.update(Message::SelectTaxRate(selected)));
+                    }
+                }
+            }
+            Message::SelectTaxRate(index) => {
+                if let Some(config_handler) = &self.config_handler
+                    && let Err(err) = self.config.set_selected_tax_rate(config_handler, index)
+                {
+                    tasks.push(self.save_settings_failed(err));
+                }
+            }
+            Message::Number(num) => self.calculator.on_number_press(num),
+            Message::Input(input) => self.calculator.on_input(input),
+            Message::Operator(operator)
+                if self.config.input_mode == InputMode::Rpn
+                    && Operator::STACK.contains(&operator) =>
+            {
+                tasks.push(self.rpn(operator));
+            }
+            Message::Operator(operator)
+                if self.config.input_mode == InputMode::Chain
+                    && Operator::BINARY.contains(&operator)
+                    && !self.calculator.expression.trim().is_empty() =>
+            {
+                tasks.push(self.chain(operator));
+            }
+            Message::Operator(operator) => match self.calculator.on_operator_press(&operator) {
+                Some(operations::Message::Evaluate) => tasks.push(self.update(Message::Evaluate)),
+                Some(operations::Message::AddTax) => tasks.push(self.apply_tax(true)),
+                Some(operations::Message::RemoveTax) => tasks.push(self.apply_tax(false)),
+                Some(operations::Message::RecallGrandTotal) => {
+                    self.insert_value(&operations::number_literal(
+                        self.config.grand_total,
+                        self.calculator.decimal_comma,
+                    ));
+                }
+                Some(operations::Message::ClearGrandTotal) => {
+                    tasks.push(self.set_grand_total(0.0));
+                }
+                Some(operations::Message::AddToMemory) => tasks.push(self.add_to_memory(1.0)),
+                Some(operations::Message::SubtractFromMemory) => {
+                    tasks.push(self.add_to_memory(-1.0))
+                }
+                Some(operations::Message::RecallMemory) => {
+                    self.insert_value(&operations::number_literal(
+                        self.config.memory,
+                        self.calculator.decimal_comma,
+                    ));
+                }
+                Some(operations::Message::ClearMemory) => tasks.push(self.set_memory(0.0)),
+                None => {}
+            },
+            Message::Evaluate => {
+                if let Some(operation) = &self.repeat
+                    && !self.calculator.outcome.is_empty()
+                    && self.calculator.expression == self.calculator.outcome
+                {
+                    let result = &self.calculator.outcome;
+                    self.calculator.expression = if result.starts_with('-') {
+                        format!("({result}){operation}")
+                    } else {
+                        format!("{result}{operation}")
+                    };
                 }
+                self.repeat = operations::last_operation(&self.calculator.expression);
+
+                let unrounded = match self.evaluate_expression() {
+                    Ok(unrounded) => unrounded,
+                    Err(operations::EvaluateError::Empty) => return Task::batch(tasks),
+                    Err(err) => {
+                        tasks.push(self.evaluate_failed(err));
+                        return Task::batch(tasks);
+                    }
+                };
+                // Inferred from what was typed, before desk percentages add numbers.
+                let (outcome, details) =
+                    self.round_outcome(&unrounded, &self.calculator.expression);
 
                 self.calculator.outcome = outcome.clone();
+                self.calculator.unrounded = Some(unrounded);
+                self.calculator.details = details;
+                self.result_notation = None;
+                if let Some((value, uncertainty)) =
+                    operations::parse_uncertainty(&outcome, self.calculator.decimal_comma)
+                    && value != 0.0
+                {
+                    let relative = format!("{:.2}", uncertainty / value.abs() * 100.0);
+                    self.calculator.details.push(fl!(
+                        "relative-uncertainty",
+                        value = if self.calculator.decimal_comma {
+                            relative.replace('.', ",")
+                        } else {
+                            relative
+                        }
+                    ));
+                }
+                tasks.push(self.push_history(self.calculator.clone()));
+                self.tape.record(
+                    &self.calculator.expression,
+                    &outcome,
+                    tape::Kind::Total,
+                    self.calculator.decimal_comma,
+                );
 
-                let mut history = self.config.history.clone();
-                history.push(self.calculator.clone());
-                if let Some(config_handler) = &self.config_handler
-                    && let Err(err) = self.config.set_history(config_handler, history)
+                if self.config.grand_total_enabled
+                    && let Some(value) =
+                        operations::parse_number(&outcome, self.calculator.decimal_comma)
                 {
-                    tracing::error!("Failed to save history: {}", err);
-                    tasks.push(
-                        self.update(Message::ShowToast("Failed to save history".to_string())),
-                    );
+                    let total = operations::add_decimals(self.config.grand_total, value);
+                    tasks.push(self.set_grand_total(total));
                 }
-                self.nav
-                    .insert()
-                    .text(history_label(&self.calculator.expression))
-                    .data(self.calculator.clone());
 
-                self.calculator.expression = outcome;
+                self.show_result(outcome);
             }
             Message::Key(modifiers, key, text) => {
                 for (key_bind, action) in &self.key_binds {
@@ -556,7 +1079,7 @@ impl Application for CosmicCalculator {
                         "-" => Some(Operator::Subtract),
                         "*" | "×" => Some(Operator::Multiply),
                         "/" | "÷" => Some(Operator::Divide),
-                        "%" => Some(Operator::Modulus),
+                        "%" => Some(Operator::Percent),
                         "(" => Some(Operator::ParenthesesOpen),
                         ")" => Some(Operator::ParenthesesClose),
                         "^" => Some(Operator::Power),
@@ -567,10 +1090,11 @@ impl Application for CosmicCalculator {
                         return self.update(Message::Operator(operator));
                     }
 
-                    // Digits and decimal separators reuse on_input validation.
+                    // Digits and decimal separators reuse on_input validation. A
+                    // typed ± starts an uncertainty; only the keypad ± negates.
                     if !c.is_empty()
                         && c.chars()
-                            .all(|ch| ch.is_ascii_digit() || ch == '.' || ch == ',')
+                            .all(|ch| ch.is_ascii_digit() || matches!(ch, '.' | ',' | '±'))
                     {
                         let mut expression = self.calculator.expression.clone();
                         expression.push_str(&c);
@@ -595,6 +1119,14 @@ impl Application for CosmicCalculator {
             }
             Message::NavMenuAction(action) => {
                 match action {
+                    NavMenuAction::Edit(entity) => {
+                        if let Some(data) = self.nav.data::<Calculator>(entity) {
+                            self.calculator.expression = data.expression.clone();
+                            self.calculator.outcome = String::new();
+                            self.calculator.unrounded = None;
+                            self.calculator.details.clear();
+                        }
+                    }
                     NavMenuAction::Delete(entity) => {
                         if let Some(data) = self.nav.data::<Calculator>(entity).cloned() {
                             let mut history = self.config.history.clone();
@@ -632,6 +1164,62 @@ impl Application for CosmicCalculator {
             Message::Resized(size) => {
                 self.button_font_size = (size.height / 22.0).clamp(10.0, 48.0);
             }
+            Message::Tape(message) => {
+                let action = self.tape.update(message, self.calculator.decimal_comma);
+                tasks.push(self.tool_action(action, Message::Tape));
+            }
+            Message::Business(message) => {
+                let action = self.business.update(message, self.calculator.decimal_comma);
+                tasks.push(self.tool_action(action, Message::Business));
+            }
+            Message::Calculus(message) => {
+                let action = self.calculus.update(message, self.calculator.decimal_comma);
+                tasks.push(self.tool_action(action, Message::Calculus));
+            }
+            Message::Plot(message) => {
+                let action = self.plot.update(message, self.calculator.decimal_comma);
+                tasks.push(self.tool_action(action, Message::Plot));
+            }
+            Message::Table(message) => {
+                let action = self.table.update(message, self.calculator.decimal_comma);
+                tasks.push(self.tool_action(action, Message::Table));
+            }
+            Message::Regression(message) => {
+                let action = self
+                    .regression
+                    .update(message, self.calculator.decimal_comma);
+                tasks.push(self.tool_action(action, Message::Regression));
+            }
+            Message::Finance(message) => {
+                let action = self.finance.update(message, self.calculator.decimal_comma);
+                tasks.push(self.tool_action(action, Message::Finance));
+            }
+            Message::Tip(message) => {
+                let action = self.tip.update(message, self.calculator.decimal_comma);
+                tasks.push(self.tool_action(action, Message::Tip));
+            }
+            Message::NumberTheory(message) => {
+                let action = self.number_theory.update(message);
+                tasks.push(self.tool_action(action, Message::NumberTheory));
+            }
+            Message::Random(message) => {
+                let action = self.random.update(message, self.calculator.decimal_comma);
+                tasks.push(self.tool_action(action, Message::Random));
+            }
+            Message::Formats(message) => {
+                let action = self.formats.update(message);
+                tasks.push(self.tool_action(action, Message::Formats));
+            }
+            Message::Ratio(message) => {
+                let action = self.ratio.update(message, self.calculator.decimal_comma);
+                tasks.push(self.tool_action(action, Message::Ratio));
+            }
+            Message::UnitPrice(message) => {
+                let action = self
+                    .unit_price
+                    .update(message, self.calculator.decimal_comma);
+                tasks.push(self.tool_action(action, Message::UnitPrice));
+            }
         }
         Task::batch(tasks)
     }
@@ -647,6 +1235,93 @@ impl Application for CosmicCalculator {
                 |url| Message::Open(url.to_string()),
                 Message::ToggleContextDrawer,
             ),
+            ContextPage::Settings => {
+                context_drawer::context_drawer(self.settings(), Message::ToggleContextDrawer)
+                    .title(fl!("settings"))
+            }
+            ContextPage::Memory => {
+                context_drawer::context_drawer(self.memory(), Message::ToggleContextDrawer)
+                    .title(fl!("memory"))
+            }
+            ContextPage::Tape => context_drawer::context_drawer(
+                self.tape
+                    .view(self.calculator.decimal_comma)
+                    .map(Message::Tape),
+                Message::ToggleContextDrawer,
+            )
+            .title(fl!("paper-tape")),
+            ContextPage::Business => context_drawer::context_drawer(
+                self.business.view().map(Message::Business),
+                Message::ToggleContextDrawer,
+            )
+            .title(fl!("business")),
+            ContextPage::Calculus => context_drawer::context_drawer(
+                self.calculus.view().map(Message::Calculus),
+                Message::ToggleContextDrawer,
+            )
+            .title(fl!("calculus")),
+            ContextPage::Plot => context_drawer::context_drawer(
+                self.plot
+                    .view(self.calculator.decimal_comma)
+                    .map(Message::Plot),
+                Message::ToggleContextDrawer,
+            )
+            .title(fl!("graph")),
+            ContextPage::Table => context_drawer::context_drawer(
+                self.table.view().map(Message::Table),
+                Message::ToggleContextDrawer,
+            )
+            .title(fl!("table-of-values")),
+            ContextPage::Regression => context_drawer::context_drawer(
+                self.regression.view().map(Message::Regression),
+                Message::ToggleContextDrawer,
+            )
+            .title(fl!("regression")),
+            ContextPage::Finance => context_drawer::context_drawer(
+                self.finance
+                    .view(self.calculator.decimal_comma)
+                    .map(Message::Finance),
+                Message::ToggleContextDrawer,
+            )
+            .title(fl!("time-value-of-money")),
+            ContextPage::Tip => context_drawer::context_drawer(
+                self.tip
+                    .view(self.calculator.decimal_comma)
+                    .map(Message::Tip),
+                Message::ToggleContextDrawer,
+            )
+            .title(fl!("tip-and-split")),
+            ContextPage::NumberTheory => context_drawer::context_drawer(
+                self.number_theory.view().map(Message::NumberTheory),
+                Message::ToggleContextDrawer,
+            )
+            .title(fl!("number-theory")),
+            ContextPage::Random => context_drawer::context_drawer(
+                self.random.view().map(Message::Random),
+                Message::ToggleContextDrawer,
+            )
+            .title(fl!("random")),
+            ContextPage::Formats => context_drawer::context_drawer(
+                self.formats
+                    .view(&self.calculator.outcome, self.calculator.decimal_comma)
+                    .map(Message::Formats),
+                Message::ToggleContextDrawer,
+            )
+            .title(fl!("number-formats")),
+            ContextPage::Ratio => context_drawer::context_drawer(
+                self.ratio
+                    .view(self.calculator.decimal_comma)
+                    .map(Message::Ratio),
+                Message::ToggleContextDrawer,
+            )
+            .title(fl!("ratio-and-proportion")),
+            ContextPage::UnitPrice => context_drawer::context_drawer(
+                self.unit_price
+                    .view(self.calculator.decimal_comma)
+                    .map(Message::UnitPrice),
+                Message::ToggleContextDrawer,
+            )
+            .title(fl!("unit-price")),
         })
     }
 
@@ -706,9 +1381,601 @@ impl Application for CosmicCalculator {
 }
 
 impl CosmicCalculator {
+    fn settings(&self) -> Element<'_, Message> {
+        let spacing = cosmic::theme::active().cosmic().spacing;
+
+        let mut taxes = widget::settings::section().title(fl!("tax-rates"));
+        if !self.config.tax_rates.is_empty() {
+            let selected = self
+                .config
+                .selected_tax_rate
+                .min(self.config.tax_rates.len() - 1);
+            taxes = taxes.add(widget::settings::item(
+                fl!("active-tax-rate"),
+                widget::dropdown(
+                    &self.tax_rate_labels,
+                    Some(selected),
+                    Message::SelectTaxRate,
+                ),
+            ));
+        }
+        for (index, label) in self.tax_rate_labels.iter().enumerate() {
+            taxes = taxes.add(widget::settings::item(
+                label.as_str(),
+                widget::button::icon(icons::get_handle("user-trash-symbolic", 14))
+                    .on_press(Message::RemoveTaxRate(index)),
+            ));
+        }
+        taxes = taxes.add(
+            widget::row::with_capacity(3)
+                .push(
+                    widget::text_input(fl!("name"), &self.tax_rate_name)
+                        .on_input(Message::TaxRateName)
+                        .on_submit(|_| Message::AddTaxRate),
+                )
+                .push(
+                    widget::text_input("%", &self.tax_rate_value)
+                        .on_input(Message::TaxRateValue)
+                        .on_submit(|_| Message::AddTaxRate)
+                        .width(Length::Fixed(80.0)),
+                )
+                .push(widget::button::standard(fl!("add")).on_press(Message::AddTaxRate))
+                .spacing(spacing.space_xxs)
+                .align_y(Alignment::Center),
+        );
+
+        widget::settings::view_column(vec![
+            widget::settings::section()
+                .title(fl!("keypad"))
+                .add(
+                    widget::settings::item::builder(fl!("desk-percent"))
+                        .description(fl!("desk-percent-description"))
+                        .toggler(self.config.percent_mode == PercentMode::Desk, |desk| {
+                            Message::SetPercentMode(if desk {
+                                PercentMode::Desk
+                            } else {
+                                PercentMode::Literal
+                            })
+                        }),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("input-mode"))
+                        .description(match self.config.input_mode {
+                            InputMode::Chain => fl!("chain-mode-description"),
+                            InputMode::Algebraic | InputMode::Rpn => fl!("input-mode-description"),
+                        })
+                        .control(widget::dropdown(
+                            &self.input_modes,
+                            InputMode::ALL
+                                .iter()
+                                .position(|mode| *mode == self.config.input_mode),
+                            |index| Message::SetInputMode(InputMode::ALL[index]),
+                        )),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("grand-total"))
+                        .description(fl!("grand-total-description"))
+                        .toggler(
+                            self.config.grand_total_enabled,
+                            Message::SetGrandTotalEnabled,
+                        ),
+                )
+                .into(),
+            widget::settings::section()
+                .title(fl!("display"))
+                .add(
+                    widget::settings::item::builder(fl!("significant-figures"))
+                        .description(fl!("significant-figures-description"))
+                        .control(widget::dropdown(
+                            &self.significant_figures,
+                            SignificantFigures::ALL
+                                .iter()
+                                .position(|figures| *figures == self.config.significant_figures),
+                            |index| Message::SetSignificantFigures(SignificantFigures::ALL[index]),
+                        )),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("decimal-places"))
+                        .description(fl!("decimal-places-description"))
+                        .control(widget::dropdown(
+                            &self.decimal_places,
+                            DecimalPlaces::ALL
+                                .iter()
+                                .position(|places| *places == self.config.decimal_places),
+                            |index| Message::SetDecimalPlaces(DecimalPlaces::ALL[index]),
+                        )),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("rounding"))
+                        .description(fl!("rounding-description"))
+                        .control(widget::dropdown(
+                            &self.roundings,
+                            Rounding::ALL
+                                .iter()
+                                .position(|rounding| *rounding == self.config.rounding),
+                            |index| Message::SetRounding(Rounding::ALL[index]),
+                        )),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("notation"))
+                        .description(fl!("notation-description"))
+                        .control(widget::dropdown(
+                            &self.notations,
+                            Notation::ALL
+                                .iter()
+                                .position(|notation| *notation == self.config.notation),
+                            |index| Message::SetNotation(Notation::ALL[index]),
+                        )),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("notation-threshold"))
+                        .description(fl!("notation-threshold-description"))
+                        .control(widget::dropdown(
+                            &self.thresholds,
+                            Threshold::ALL
+                                .iter()
+                                .position(|threshold| *threshold == self.config.notation_threshold),
+                            |index| Message::SetNotationThreshold(Threshold::ALL[index]),
+                        )),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("digit-grouping"))
+                        .description(fl!("digit-grouping-description"))
+                        .control(widget::dropdown(
+                            &self.digit_groupings,
+                            DigitGrouping::ALL
+                                .iter()
+                                .position(|grouping| *grouping == self.config.digit_grouping),
+                            |index| Message::SetDigitGrouping(DigitGrouping::ALL[index]),
+                        )),
+                )
+                .into(),
+            taxes.into(),
+        ])
+        .into()
+    }
+
+    fn memory(&self) -> Element<'_, Message> {
+        let spacing = cosmic::theme::active().cosmic().spacing;
+
+        let register = widget::settings::section().add(widget::settings::item(
+            "M",
+            widget::text::body(self.shown(&operations::number_literal(
+                self.config.memory,
+                self.calculator.decimal_comma,
+            ))),
+        ));
+
+        let mut slots = widget::settings::section().title(fl!("named-memories"));
+        for (index, memory) in self.config.memories.iter().enumerate() {
+            slots = slots.add(widget::settings::item(
+                memory.name.as_str(),
+                widget::row::with_capacity(3)
+                    .push(widget::text::body(self.shown(&memory.value)))
+                    .push(
+                        widget::button::standard(fl!("insert"))
+                            .on_press(Message::InsertMemory(index)),
+                    )
+                    .push(
+                        widget::button::icon(icons::get_handle("user-trash-symbolic", 14))
+                            .on_press(Message::RemoveMemory(index)),
+                    )
+                    .spacing(spacing.space_xxs)
+                    .align_y(Alignment::Center),
+            ));
+        }
+        slots = slots.add(
+            widget::row::with_capacity(2)
+                .push(
+                    widget::text_input(fl!("name"), &self.memory_name)
+                        .on_input(Message::MemoryName)
+                        .on_submit(|_| Message::StoreMemory),
+                )
+                .push(widget::button::standard(fl!("store")).on_press(Message::StoreMemory))
+                .spacing(spacing.space_xxs)
+                .align_y(Alignment::Center),
+        );
+
+        widget::settings::view_column(vec![
+            register.into(),
+            slots.into(),
+            widget::text::caption(fl!("store-description")).into(),
+        ])
+        .into()
+    }
+
     fn update_config(&mut self) -> Task<Message> {
         cosmic::command::set_theme(self.config.app_theme.theme())
     }
+
+    /// The current expression with percentages rewritten for the active mode.
+    fn prepared_expression(&self) -> String {
+        let expression = display::plain_expression(
+            &self.calculator.expression,
+            self.calculator.decimal_comma,
+            &self.grouped_results,
+        );
+        operations::apply_percent_mode(&expression, self.config.percent_mode)
+    }
+
+    /// Evaluates the expression being typed, returning the result as qalc
+    /// printed it, with every digit it computed when the display rounds.
+    fn evaluate_expression(&self) -> Result<String, operations::EvaluateError> {
+        let expression = self.prepared_expression();
+        operations::evaluate_with(
+            &expression,
+            self.calculator.decimal_comma,
+            self.rounding_settings(),
+        )
+    }
+
+    /// qalc settings for results that will be rounded: rounding needs more
+    /// digits than are shown, or ties could not be told apart, so qalc
+    /// computes twenty significant digits rather than ten and prints them
+    /// all rather than the first nine decimals.
+    fn rounding_settings(&self) -> &'static [&'static str] {
+        if self.config.significant_figures == SignificantFigures::Off
+            && self.config.decimal_places == DecimalPlaces::Floating
+        {
+            &[]
+        } else {
+            &["precision 20", "maxdeci -1"]
+        }
+    }
+
+    /// Rounds a numeric `outcome` of `expression` to the significant figures
+    /// or decimal places in the settings, which take precedence in that
+    /// order, and writes it in the chosen notation. Returns the rounded
+    /// outcome with any rounding note.
+    fn round_outcome(&self, outcome: &str, expression: &str) -> (String, Vec<String>) {
+        let decimal_comma = self.calculator.decimal_comma;
+        let Some(mut decimal) = display::Decimal::parse(outcome, decimal_comma) else {
+            return (outcome.to_string(), vec![]);
+        };
+        let figures = match self.config.significant_figures {
+            SignificantFigures::Off => None,
+            SignificantFigures::Inferred => {
+                let expression =
+                    display::plain_expression(expression, decimal_comma, &self.grouped_results);
+                display::inferred_figures(&expression, decimal_comma)
+            }
+            SignificantFigures::Fixed(figures) => Some(figures),
+        };
+
+        let mut details = vec![];
+        match (figures, self.config.decimal_places) {
+            (Some(figures), _) => {
+                if decimal.round_significant(figures, self.config.rounding) {
+                    details.push(fl!("rounded-significant-figures", count = figures));
+                }
+            }
+            (None, DecimalPlaces::Fixed(places)) => {
+                decimal.round_decimals(places, self.config.rounding);
+            }
+            (None, DecimalPlaces::Floating) => {}
+        }
+        (decimal.format(&self.style()), details)
+    }
+
+    /// Writes a stored value, such as a memory or a stack level, the way
+    /// results are shown.
+    fn shown(&self, value: &str) -> String {
+        self.round_outcome(value, "").0
+    }
+
+    /// Inserts a stored value into the expression as it is shown.
+    fn insert_value(&mut self, value: &str) {
+        let value = self.shown(value);
+        self.grouped_results.push(value.clone());
+        self.calculator.insert(&value);
+    }
+
+    /// Writes a result the app formatted into the expression, remembering it
+    /// so its digit grouping can be removed before evaluation.
+    fn show_result(&mut self, result: String) {
+        self.grouped_results = vec![result.clone()];
+        self.calculator.expression = result;
+    }
+
+    /// How results are written with the current settings.
+    fn style(&self) -> display::Style {
+        display::Style {
+            decimal_comma: self.calculator.decimal_comma,
+            notation: self.config.notation,
+            threshold: self.config.notation_threshold.0,
+            grouping: match self.config.digit_grouping {
+                DigitGrouping::Locale => self.locale_grouping,
+                grouping => grouping,
+            },
+        }
+    }
+
+    /// Chain mode: completes the pending operation before appending
+    /// `operator`, so the display shows the running result.
+    fn chain(&mut self, operator: Operator) -> Task<Message> {
+        if self.calculator.replace_trailing_operator(&operator) {
+            return Task::none();
+        }
+
+        let decimal_comma = self.calculator.decimal_comma;
+        if operations::parse_number(&self.calculator.expression, decimal_comma).is_none() {
+            match self.evaluate_expression() {
+                Ok(unrounded) => {
+                    let (outcome, details) =
+                        self.round_outcome(&unrounded, &self.calculator.expression);
+                    self.tape.record(
+                        &self.calculator.expression,
+                        &outcome,
+                        tape::Kind::Subtotal,
+                        decimal_comma,
+                    );
+                    self.show_result(outcome.clone());
+                    self.calculator.outcome = outcome;
+                    self.calculator.unrounded = Some(unrounded);
+                    self.calculator.details = details;
+                    self.result_notation = None;
+                }
+                Err(err) => return self.evaluate_failed(err),
+            }
+        }
+        self.calculator.add_operator(operator);
+        Task::none()
+    }
+
+    /// RPN mode: applies a stack key, pushing the typed entry first.
+    fn rpn(&mut self, operator: Operator) -> Task<Message> {
+        let entry = display::plain_expression(
+            &self.calculator.expression,
+            self.calculator.decimal_comma,
+            &self.grouped_results,
+        )
+        .trim()
+        .to_string();
+        // With a number being typed, C and ± edit it rather than the stack.
+        if !entry.is_empty() && matches!(operator, Operator::Clear | Operator::Negate) {
+            self.calculator.on_operator_press(&operator);
+            return Task::none();
+        }
+
+        let mut rpn = self.rpn.clone();
+        let entry = match rpn.apply(&operator, &entry, self.calculator.decimal_comma) {
+            Ok(entry) => entry,
+            Err(err) => return self.evaluate_failed(err),
+        };
+        self.rpn = rpn;
+        self.calculator.clear();
+
+        let mut tasks = vec![];
+        if let Some(config_handler) = &self.config_handler
+            && let Err(err) = self
+                .config
+                .set_rpn_stack(config_handler, self.rpn.stack.clone())
+        {
+            tasks.push(self.save_settings_failed(err));
+        }
+        if let Some(mut entry) = entry {
+            let (outcome, details) = self.round_outcome(&entry.outcome, "");
+            entry.unrounded = Some(std::mem::replace(&mut entry.outcome, outcome));
+            entry.details.extend(details);
+            tasks.push(self.push_history(entry));
+        }
+        Task::batch(tasks)
+    }
+
+    /// Adds or removes the active tax rate from the current value, keeping the
+    /// net, tax and gross amounts as details of the result.
+    fn apply_tax(&mut self, add: bool) -> Task<Message> {
+        let Some(TaxRate { name, rate }) = self.config.tax_rate().cloned() else {
+            return Task::none();
+        };
+        let value = self.prepared_expression();
+        if value.trim().is_empty() {
+            return Task::none();
+        }
+        let entered = self.calculator.expression.clone();
+
+        let decimal_comma = self.calculator.decimal_comma;
+        let rate = operations::number_literal(rate, decimal_comma);
+        let factor = format!("(1+{rate}/100)");
+        let (net, gross) = if add {
+            (format!("({value})"), format!("({value})*{factor}"))
+        } else {
+            (format!("({value})/{factor}"), format!("({value})"))
+        };
+        let expressions = [net.clone(), format!("{gross}-{net}"), gross.clone()];
+        let unrounded =
+            match operations::evaluate_batch(&expressions, decimal_comma, self.rounding_settings())
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()
+            {
+                Ok(values) => values,
+                Err(err) => return self.evaluate_failed(err),
+            };
+        // Each amount is rounded on its own, as it would be written on an
+        // invoice.
+        let values = unrounded
+            .iter()
+            .map(|value| self.shown(value))
+            .collect::<Vec<_>>();
+        let [net_value, tax_value, gross_value] = [&values[0], &values[1], &values[2]];
+
+        let (base, sign) = if add {
+            (net_value, "+")
+        } else {
+            (gross_value, "−")
+        };
+        let outcome = if add { gross_value } else { net_value }.clone();
+        self.tape
+            .record(&entered, base, tape::Kind::Subtotal, decimal_comma);
+        self.tape
+            .entry(sign, format!("{tax_value} ({name} {rate}%)"));
+        self.tape.result(&outcome, tape::Kind::Total, decimal_comma);
+
+        self.calculator.expression = if add { gross } else { net };
+        self.calculator.outcome = outcome.clone();
+        self.calculator.unrounded = Some(unrounded[if add { 2 } else { 0 }].clone());
+        self.calculator.details = vec![
+            fl!("tax-net", value = net_value.as_str()),
+            fl!(
+                "tax-amount",
+                name = name.as_str(),
+                rate = rate.as_str(),
+                value = tax_value.as_str()
+            ),
+            fl!("tax-gross", value = gross_value.as_str()),
+        ];
+        let task = self.push_history(self.calculator.clone());
+        self.show_result(outcome);
+        self.repeat = None;
+        task
+    }
+
+    /// Evaluates the current expression for the memory keys.
+    fn current_value(&self) -> Result<String, operations::EvaluateError> {
+        if self.config.input_mode == InputMode::Rpn
+            && self.calculator.expression.trim().is_empty()
+            && let Some(x) = self.rpn.stack.last()
+        {
+            return Ok(x.clone());
+        }
+        let expression = self.prepared_expression();
+        operations::evaluate(&expression, self.calculator.decimal_comma)
+    }
+
+    /// Adds the current value times `sign` to the M register.
+    fn add_to_memory(&mut self, sign: f64) -> Task<Message> {
+        let value = match self.current_value() {
+            Ok(value) => value,
+            Err(operations::EvaluateError::Empty) => return Task::none(),
+            Err(err) => return self.evaluate_failed(err),
+        };
+        let Some(value) = operations::parse_number(&value, self.calculator.decimal_comma) else {
+            return self.evaluate_failed(operations::EvaluateError::InvalidInput);
+        };
+        let memory = operations::add_decimals(self.config.memory, sign * value);
+        self.set_memory(memory)
+    }
+
+    fn set_memory(&mut self, memory: f64) -> Task<Message> {
+        if let Some(config_handler) = &self.config_handler
+            && let Err(err) = self.config.set_memory(config_handler, memory)
+        {
+            return self.save_settings_failed(err);
+        }
+        Task::none()
+    }
+
+    fn set_memories(&mut self, memories: Vec<Memory>) -> Task<Message> {
+        if let Some(config_handler) = &self.config_handler
+            && let Err(err) = self.config.set_memories(config_handler, memories)
+        {
+            return self.save_settings_failed(err);
+        }
+        Task::none()
+    }
+
+    fn set_grand_total(&mut self, grand_total: f64) -> Task<Message> {
+        if let Some(config_handler) = &self.config_handler
+            && let Err(err) = self.config.set_grand_total(config_handler, grand_total)
+        {
+            return self.save_settings_failed(err);
+        }
+        Task::none()
+    }
+
+    fn set_tax_rates(&mut self, tax_rates: Vec<TaxRate>) -> Task<Message> {
+        let mut task = Task::none();
+        if let Some(config_handler) = &self.config_handler
+            && let Err(err) = self.config.set_tax_rates(config_handler, tax_rates)
+        {
+            task = self.save_settings_failed(err);
+        }
+        self.refresh_tax_rate_labels();
+        task
+    }
+
+    fn refresh_tax_rate_labels(&mut self) {
+        self.tax_rate_labels = self
+            .config
+            .tax_rates
+            .iter()
+            .map(|TaxRate { name, rate }| {
+                format!(
+                    "{name} ({}%)",
+                    operations::number_literal(*rate, self.calculator.decimal_comma)
+                )
+            })
+            .collect();
+    }
+
+    /// Logs a failed settings write and reports it with a toast.
+    fn save_settings_failed(&mut self, err: cosmic_config::Error) -> Task<Message> {
+        tracing::error!("Failed to save settings: {}", err);
+        self.update(Message::ShowToast("Failed to save settings".to_string()))
+    }
+
+    /// Stores `entry` in the persisted history and the sidebar.
+    fn push_history(&mut self, entry: Calculator) -> Task<Message> {
+        let mut task = Task::none();
+        let mut history = self.config.history.clone();
+        history.push(entry.clone());
+        if let Some(config_handler) = &self.config_handler
+            && let Err(err) = self.config.set_history(config_handler, history)
+        {
+            tracing::error!("Failed to save history: {}", err);
+            task = self.update(Message::ShowToast("Failed to save history".to_string()));
+        }
+        self.nav
+            .insert()
+            .text(history_label(&entry.expression))
+            .data(entry);
+        task
+    }
+
+    /// Carries out what a tool panel asked for, wrapping its messages with `map`.
+    fn tool_action<M: Send + 'static>(
+        &mut self,
+        action: tool::Action<M>,
+        map: fn(M) -> Message,
+    ) -> Task<Message> {
+        match action {
+            tool::Action::None => Task::none(),
+            tool::Action::Task(task) => task.map(move |message| cosmic::Action::App(map(message))),
+            tool::Action::History(entry) => self.push_history(entry),
+            tool::Action::Insert(value) => {
+                self.calculator.insert(&value);
+                widget::text_input::focus(self.input_id.clone())
+            }
+            tool::Action::Copy(text) => Task::batch([
+                cosmic::iced::clipboard::write(text),
+                self.update(Message::ShowToast(fl!("copied"))),
+            ]),
+            tool::Action::Export(stem, extension, contents) => {
+                match tool::export(stem, extension, &contents) {
+                    Ok(path) => self.update(Message::ShowToast(fl!(
+                        "exported",
+                        path = path.display().to_string()
+                    ))),
+                    Err(err) => {
+                        tracing::error!("Failed to export: {}", err);
+                        self.update(Message::ShowToast("Failed to export".to_string()))
+                    }
+                }
+            }
+            tool::Action::Error(err) => self.evaluate_failed(err),
+        }
+    }
+
+    /// Logs a failed evaluation and reports it with a toast.
+    fn evaluate_failed(&mut self, err: operations::EvaluateError) -> Task<Message> {
+        match &err {
+            operations::EvaluateError::Qalc(error) => {
+                tracing::error!("An error occurred: {}", error)
+            }
+            _ => tracing::error!("{err}"),
+        }
+        self.update(Message::ShowToast(err.to_string()))
+    }
 }
 
 // Sidebar label: expression truncated to fit the sidebar width.
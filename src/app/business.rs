@@ -0,0 +1,157 @@
+use cosmic::{Element, iced::Length, widget};
+
+use crate::app::{
+    operations::{self, EvaluateError},
+    tool::Action,
+};
+use crate::fl;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Cost,
+    Sell,
+    Margin,
+    Markup,
+}
+
+impl Field {
+    const ALL: [Self; 4] = [Self::Cost, Self::Sell, Self::Margin, Self::Markup];
+
+    fn index(&self) -> usize {
+        Self::ALL
+            .iter()
+            .position(|field| field == self)
+            .unwrap_or(0)
+    }
+
+    fn label(&self) -> String {
+        match self {
+            Self::Cost => fl!("cost"),
+            Self::Sell => fl!("sell-price"),
+            Self::Margin => fl!("margin"),
+            Self::Markup => fl!("markup"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Field(Field, String),
+    Calculate,
+    Insert(Field),
+    Clear,
+}
+
+#[derive(Default)]
+pub struct Business {
+    fields: [String; 4],
+    /// Fields in the order they were last edited, most recent last.
+    edited: Vec<Field>,
+}
+
+/// Completes cost, sell price, margin % and markup % from any two of them,
+/// except margin and markup together, which fix no amount.
+fn solve(known: [(Field, f64); 2]) -> Option<[f64; 4]> {
+    let mut values = [None; 4];
+    for (field, value) in known {
+        values[field.index()] = Some(value);
+    }
+    let (cost, sell) = match values {
+        [Some(cost), Some(sell), ..] => (cost, sell),
+        [Some(cost), None, Some(margin), _] => (cost, cost / (1.0 - margin / 100.0)),
+        [Some(cost), None, None, Some(markup)] => (cost, cost * (1.0 + markup / 100.0)),
+        [None, Some(sell), Some(margin), _] => (sell * (1.0 - margin / 100.0), sell),
+        [None, Some(sell), None, Some(markup)] => (sell / (1.0 + markup / 100.0), sell),
+        _ => return None,
+    };
+    let margin = (sell - cost) / sell * 100.0;
+    let markup = (sell - cost) / cost * 100.0;
+    let values = [cost, sell, margin, markup];
+    values
+        .iter()
+        .all(|value| value.is_finite())
+        .then_some(values)
+}
+
+impl Business {
+    pub fn update(&mut self, message: Message, decimal_comma: bool) -> Action<Message> {
+        match message {
+            Message::Field(field, value) => {
+                self.fields[field.index()] = value;
+                self.edited.retain(|edited| edited != &field);
+                self.edited.push(field);
+            }
+            Message::Calculate => return self.calculate(decimal_comma),
+            Message::Insert(field) => {
+                let value = self.fields[field.index()].trim();
+                if !value.is_empty() {
+                    return Action::Insert(value.to_string());
+                }
+            }
+            Message::Clear => *self = Self::default(),
+        }
+        Action::None
+    }
+
+    /// Solves from the two most recently edited fields that hold a number.
+    fn calculate(&mut self, decimal_comma: bool) -> Action<Message> {
+        let known = self
+            .edited
+            .iter()
+            .rev()
+            .filter_map(|field| {
+                operations::parse_number(&self.fields[field.index()], decimal_comma)
+                    .map(|value| (*field, value))
+            })
+            .take(2)
+            .collect::<Vec<_>>();
+        let Some(values) = <[(Field, f64); 2]>::try_from(known).ok().and_then(solve) else {
+            return Action::Error(EvaluateError::InvalidInput);
+        };
+
+        for (field, value) in self.fields.iter_mut().zip(values) {
+            *field = operations::format_amount(value, decimal_comma);
+        }
+        Action::None
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let spacing = cosmic::theme::active().cosmic().spacing;
+
+        let mut inputs = widget::settings::section();
+        for (field, value) in Field::ALL.iter().zip(&self.fields) {
+            let field = *field;
+            let placeholder = match field {
+                Field::Cost | Field::Sell => "",
+                Field::Margin | Field::Markup => "%",
+            };
+            inputs = inputs.add(widget::settings::item(
+                field.label(),
+                widget::row::with_capacity(2)
+                    .push(
+                        widget::text_input(placeholder, value)
+                            .on_input(move |value| Message::Field(field, value))
+                            .on_submit(|_| Message::Calculate)
+                            .width(Length::Fixed(140.0)),
+                    )
+                    .push(widget::button::standard(fl!("insert")).on_press_maybe(
+                        (!value.trim().is_empty()).then_some(Message::Insert(field)),
+                    ))
+                    .spacing(spacing.space_xxs)
+                    .align_y(cosmic::iced::Alignment::Center),
+            ));
+        }
+
+        let controls = widget::row::with_capacity(2)
+            .push(widget::button::suggested(fl!("calculate")).on_press(Message::Calculate))
+            .push(widget::button::standard(fl!("clear")).on_press(Message::Clear))
+            .spacing(spacing.space_xxs);
+
+        widget::settings::view_column(vec![
+            widget::text::caption(fl!("business-description")).into(),
+            inputs.into(),
+            controls.into(),
+        ])
+        .into()
+    }
+}
@@ -0,0 +1,236 @@
+use cosmic::{Element, widget};
+
+use crate::app::operations::{self, Calculator, EvaluateError};
+use crate::app::tool::Action;
+use crate::fl;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Operation {
+    #[default]
+    Derivative,
+    Integral,
+    Limit,
+    Sum,
+    Product,
+}
+
+impl Operation {
+    const ALL: [Self; 5] = [
+        Self::Derivative,
+        Self::Integral,
+        Self::Limit,
+        Self::Sum,
+        Self::Product,
+    ];
+
+    fn label(&self) -> String {
+        match self {
+            Self::Derivative => fl!("derivative"),
+            Self::Integral => fl!("integral"),
+            Self::Limit => fl!("limit"),
+            Self::Sum => fl!("sum"),
+            Self::Product => fl!("product"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Operation(usize),
+    Function(String),
+    Variable(String),
+    Lower(String),
+    Upper(String),
+    Evaluate,
+}
+
+pub struct Calculus {
+    operation: Operation,
+    operations: Vec<String>,
+    function: String,
+    variable: String,
+    lower: String,
+    upper: String,
+    symbolic: Option<String>,
+    numeric: Option<String>,
+}
+
+impl Default for Calculus {
+    fn default() -> Self {
+        Self {
+            operation: Operation::default(),
+            operations: Operation::ALL.iter().map(Operation::label).collect(),
+            function: String::new(),
+            variable: String::from("x"),
+            lower: String::new(),
+            upper: String::new(),
+            symbolic: None,
+            numeric: None,
+        }
+    }
+}
+
+impl Calculus {
+    /// Builds the qalc expression for the current fields.
+    pub fn expression(&self) -> Option<String> {
+        let function = self.function.trim();
+        let variable = match self.variable.trim() {
+            "" => "x",
+            variable => variable,
+        };
+        let (lower, upper) = (self.lower.trim(), self.upper.trim());
+        if function.is_empty() {
+            return None;
+        }
+
+        Some(match self.operation {
+            Operation::Derivative => format!("diff({function}, {variable})"),
+            Operation::Integral if lower.is_empty() && upper.is_empty() => {
+                format!("integrate({function}, {variable})")
+            }
+            Operation::Integral => {
+                if lower.is_empty() || upper.is_empty() {
+                    return None;
+                }
+                format!("integrate({function}, {lower}, {upper}, {variable})")
+            }
+            Operation::Limit => {
+                if lower.is_empty() {
+                    return None;
+                }
+                format!("limit({function}, {lower}, {variable})")
+            }
+            Operation::Sum | Operation::Product => {
+                if lower.is_empty() || upper.is_empty() {
+                    return None;
+                }
+                let name = match self.operation {
+                    Operation::Sum => "sum",
+                    _ => "product",
+                };
+                format!("{name}({function}, {lower}, {upper}, {variable})")
+            }
+        })
+    }
+
+    /// Applies `message`, returning a history entry once an expression was evaluated.
+    pub fn update(&mut self, message: Message, decimal_comma: bool) -> Action<Message> {
+        match message {
+            Message::Operation(index) => {
+                self.operation = Operation::ALL[index];
+                self.symbolic = None;
+                self.numeric = None;
+            }
+            Message::Function(function) => self.function = function,
+            Message::Variable(variable) => self.variable = variable,
+            Message::Lower(lower) => self.lower = lower,
+            Message::Upper(upper) => self.upper = upper,
+            Message::Evaluate => {
+                let Some(expression) = self.expression() else {
+                    return Action::Error(EvaluateError::Empty);
+                };
+
+                let symbolic = match operations::evaluate(&expression, decimal_comma) {
+                    Ok(symbolic) => symbolic,
+                    Err(err) => return Action::Error(err),
+                };
+                // The numeric form is best effort: indefinite results have none.
+                let numeric = operations::evaluate_with(
+                    &expression,
+                    decimal_comma,
+                    &["approximation approximate"],
+                )
+                .ok()
+                .filter(|numeric| numeric != &symbolic);
+
+                self.symbolic = Some(symbolic.clone());
+                self.numeric = numeric.clone();
+
+                return Action::History(Calculator {
+                    expression,
+                    outcome: symbolic,
+                    decimal_comma,
+                    details: numeric.map(|n| format!("≈ {n}")).into_iter().collect(),
+                    unrounded: None,
+                });
+            }
+        }
+        Action::None
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let (lower, upper) = match self.operation {
+            Operation::Derivative => (None, None),
+            Operation::Limit => (Some(fl!("approaches")), None),
+            Operation::Integral | Operation::Sum | Operation::Product => {
+                (Some(fl!("lower-bound")), Some(fl!("upper-bound")))
+            }
+        };
+
+        let mut section = widget::settings::section()
+            .add(widget::settings::item(
+                fl!("operation"),
+                widget::dropdown(
+                    &self.operations,
+                    Operation::ALL.iter().position(|o| o == &self.operation),
+                    Message::Operation,
+                ),
+            ))
+            .add(widget::settings::item(
+                fl!("function"),
+                widget::text_input("x^2", &self.function)
+                    .on_input(Message::Function)
+                    .on_submit(|_| Message::Evaluate),
+            ))
+            .add(widget::settings::item(
+                fl!("variable"),
+                widget::text_input("x", &self.variable).on_input(Message::Variable),
+            ));
+
+        if let Some(label) = lower {
+            section = section.add(widget::settings::item(
+                label,
+                widget::text_input("", &self.lower)
+                    .on_input(Message::Lower)
+                    .on_submit(|_| Message::Evaluate),
+            ));
+        }
+        if let Some(label) = upper {
+            section = section.add(widget::settings::item(
+                label,
+                widget::text_input("", &self.upper)
+                    .on_input(Message::Upper)
+                    .on_submit(|_| Message::Evaluate),
+            ));
+        }
+
+        let mut results = widget::settings::section().title(fl!("result"));
+        if let Some(expression) = self.expression() {
+            results = results.add(widget::settings::item(
+                fl!("expression"),
+                widget::text::body(expression),
+            ));
+        }
+        if let Some(symbolic) = &self.symbolic {
+            results = results.add(widget::settings::item(
+                fl!("symbolic"),
+                widget::text::body(symbolic.as_str()),
+            ));
+        }
+        if let Some(numeric) = &self.numeric {
+            results = results.add(widget::settings::item(
+                fl!("numeric"),
+                widget::text::body(numeric.as_str()),
+            ));
+        }
+
+        widget::settings::view_column(vec![
+            section.into(),
+            widget::button::suggested(fl!("evaluate"))
+                .on_press(Message::Evaluate)
+                .into(),
+            results.into(),
+        ])
+        .into()
+    }
+}
@@ -13,6 +13,24 @@ pub const CONFIG_VERSION: u64 = 1;
 pub struct CalculatorConfig {
     pub app_theme: AppTheme,
     pub history: Vec<Calculator>,
+    pub percent_mode: PercentMode,
+    pub input_mode: InputMode,
+    pub tax_rates: Vec<TaxRate>,
+    pub selected_tax_rate: usize,
+    pub grand_total_enabled: bool,
+    /// Sum of every result committed with `=` while the GT register is on.
+    pub grand_total: f64,
+    /// The M register used by the M+, M−, MR and MC keys.
+    pub memory: f64,
+    pub memories: Vec<Memory>,
+    /// The RPN stack, X last, kept between sessions.
+    pub rpn_stack: Vec<String>,
+    pub significant_figures: SignificantFigures,
+    pub rounding: Rounding,
+    pub decimal_places: DecimalPlaces,
+    pub notation: Notation,
+    pub notation_threshold: Threshold,
+    pub digit_grouping: DigitGrouping,
 }
 
 impl CalculatorConfig {
@@ -31,6 +49,13 @@ impl CalculatorConfig {
             None => CalculatorConfig::default(),
         }
     }
+
+    /// The rate used by the tax keys, if any are configured.
+    pub fn tax_rate(&self) -> Option<&TaxRate> {
+        self.tax_rates
+            .get(self.selected_tax_rate)
+            .or(self.tax_rates.first())
+    }
 }
 
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
@@ -58,3 +83,177 @@ impl AppTheme {
         }
     }
 }
+
+/// How the `%` key combines with the operator before it.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum PercentMode {
+    /// `200 + 10%` is 220, as on desk calculators.
+    Desk,
+    /// `%` is handed to qalc unchanged, so `200 + 10%` is 200.1, as it
+    /// always was before desk percentages could be turned on.
+    #[default]
+    Literal,
+}
+
+/// How operator keys combine with the expression typed so far.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum InputMode {
+    /// Operator precedence applies, so `2 + 3 × 4` is 14.
+    #[default]
+    Algebraic,
+    /// Each operator key first completes the pending operation, so
+    /// `2 + 3 × 4` is 20, as on four-function calculators.
+    Chain,
+    /// Reverse Polish notation: operands go on a stack with ENTER and
+    /// operators act on it, so `2 ENTER 3 +` is 5.
+    Rpn,
+}
+
+impl InputMode {
+    pub const ALL: [Self; 3] = [Self::Algebraic, Self::Chain, Self::Rpn];
+}
+
+/// How many significant figures results are rounded to.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum SignificantFigures {
+    /// Results are shown with up to nine decimals, as qalc prints them.
+    #[default]
+    Off,
+    /// As many as the least precise number in the expression, as is usual
+    /// for measurements.
+    Inferred,
+    Fixed(u32),
+}
+
+impl SignificantFigures {
+    /// The choices offered in settings. qalc computes ten significant
+    /// digits, so more could not be shown.
+    pub const ALL: [Self; 12] = [
+        Self::Off,
+        Self::Inferred,
+        Self::Fixed(1),
+        Self::Fixed(2),
+        Self::Fixed(3),
+        Self::Fixed(4),
+        Self::Fixed(5),
+        Self::Fixed(6),
+        Self::Fixed(7),
+        Self::Fixed(8),
+        Self::Fixed(9),
+        Self::Fixed(10),
+    ];
+}
+
+/// How a result is rounded to the digits shown, like the rounding switch
+/// on a desk calculator.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Rounding {
+    /// 2.5 becomes 3 and -2.5 becomes -3.
+    #[default]
+    HalfUp,
+    /// Ties go to the even digit, so 2.5 becomes 2 and 3.5 becomes 4.
+    HalfEven,
+    /// Extra digits are dropped, rounding towards zero.
+    Truncate,
+    /// Any extra digit rounds away from zero.
+    Up,
+}
+
+impl Rounding {
+    pub const ALL: [Self; 4] = [Self::HalfUp, Self::HalfEven, Self::Truncate, Self::Up];
+}
+
+/// The decimal place switch: a fixed number of decimals, or floating.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum DecimalPlaces {
+    /// As many decimals as the result needs, up to nine.
+    #[default]
+    Floating,
+    Fixed(u32),
+}
+
+impl DecimalPlaces {
+    pub const ALL: [Self; 4] = [
+        Self::Floating,
+        Self::Fixed(0),
+        Self::Fixed(2),
+        Self::Fixed(4),
+    ];
+}
+
+/// How results are written: 123456 in normal notation is 1.23456×10⁵ in
+/// scientific, 123.456×10³ in engineering and 123.456 k with SI prefixes.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Notation {
+    #[default]
+    Normal,
+    Scientific,
+    Engineering,
+    Si,
+}
+
+impl Notation {
+    pub const ALL: [Self; 4] = [Self::Normal, Self::Scientific, Self::Engineering, Self::Si];
+
+    /// The notation after this one when a result is clicked.
+    pub fn next(&self) -> Self {
+        let index = Self::ALL.iter().position(|notation| notation == self);
+        Self::ALL[index.map_or(0, |index| (index + 1) % Self::ALL.len())]
+    }
+}
+
+/// The power of ten from which normal notation switches to scientific.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Threshold(pub u32);
+
+impl Default for Threshold {
+    fn default() -> Self {
+        Self(12)
+    }
+}
+
+impl Threshold {
+    pub const ALL: [Self; 5] = [Self(6), Self(9), Self(12), Self(15), Self(20)];
+}
+
+/// How the digits of a result's whole part are grouped for reading.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum DigitGrouping {
+    /// Results are shown ungrouped, as before grouping was added.
+    #[default]
+    Off,
+    /// The separator and group sizes of the system locale.
+    Locale,
+    /// Groups of three, as in 12,345,678.
+    Thousands,
+    /// Three digits, then groups of two for lakhs and crores, as in
+    /// 1,23,45,678.
+    Indian,
+    /// Groups of three split by a thin space, as in 12 345 678.
+    ThinSpace,
+}
+
+impl DigitGrouping {
+    pub const ALL: [Self; 5] = [
+        Self::Off,
+        Self::Locale,
+        Self::Thousands,
+        Self::Indian,
+        Self::ThinSpace,
+    ];
+}
+
+/// A named tax rate for the TAX+ and TAX− keys, e.g. "VAT" at 20%.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TaxRate {
+    pub name: String,
+    /// Rate in percent.
+    pub rate: f64,
+}
+
+/// A named memory slot holding a result as qalc printed it.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Memory {
+    pub name: String,
+    pub value: String,
+}
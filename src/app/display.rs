@@ -0,0 +1,806 @@
+use crate::app::config::{DigitGrouping, Notation, Rounding};
+
+const SUPERSCRIPTS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+
+/// Separators for thin-space grouping. Results use the thin space; the
+/// narrow no-break space many locales group with is read as well.
+pub const THIN_SPACES: [char; 2] = ['\u{2009}', '\u{202F}'];
+
+/// SI prefixes by power of ten.
+const SI_PREFIXES: [(i32, &str); 21] = [
+    (-30, "q"),
+    (-27, "r"),
+    (-24, "y"),
+    (-21, "z"),
+    (-18, "a"),
+    (-15, "f"),
+    (-12, "p"),
+    (-9, "n"),
+    (-6, "µ"),
+    (-3, "m"),
+    (0, ""),
+    (3, "k"),
+    (6, "M"),
+    (9, "G"),
+    (12, "T"),
+    (15, "P"),
+    (18, "E"),
+    (21, "Z"),
+    (24, "Y"),
+    (27, "R"),
+    (30, "Q"),
+];
+
+/// How results are written out.
+#[derive(Debug, Clone, Copy)]
+pub struct Style {
+    pub decimal_comma: bool,
+    pub notation: Notation,
+    /// Normal notation switches to scientific from this power of ten, for
+    /// large and small results alike.
+    pub threshold: u32,
+    /// The locale's grouping is looked up by the caller; left unresolved it
+    /// groups in thousands.
+    pub grouping: DigitGrouping,
+}
+
+/// A decimal result as qalc printed it, kept as digits so that rounding is
+/// exact rather than subject to binary float error.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decimal {
+    negative: bool,
+    /// Digits from the most significant one down, e.g. `[1, 2, 3]` for 12.3.
+    digits: Vec<u8>,
+    /// Power of ten of the first digit, e.g. 1 for 12.3.
+    exponent: i32,
+    /// qalc marked the result as approximate with `≈`.
+    approximate: bool,
+}
+
+impl Decimal {
+    /// Reads a plain numeric result such as `-1234.5`, `≈ 0.333333333` or
+    /// `1.2E-7`. Anything else, such as a result with units, is `None`.
+    pub fn parse(text: &str, decimal_comma: bool) -> Option<Self> {
+        let text = plain(text, decimal_comma);
+        let text = text.trim();
+        let (approximate, text) = match text.strip_prefix('≈') {
+            Some(text) => (true, text),
+            None => (false, text),
+        };
+        let text = text.replace('−', "-").replace(char::is_whitespace, "");
+        let (negative, text) = match text.strip_prefix('-') {
+            Some(text) => (true, text),
+            None => (false, text.as_str()),
+        };
+
+        let (mantissa, exponent) = ["×10^", "*10^", "E", "e"]
+            .iter()
+            .find_map(|separator| text.split_once(separator))
+            .map_or(Some((text, 0)), |(mantissa, exponent)| {
+                let exponent = exponent.trim_matches(['(', ')']);
+                Some((mantissa, exponent.parse::<i32>().ok()?))
+            })?;
+
+        let separator = if decimal_comma { ',' } else { '.' };
+        let (integer, fraction) = mantissa.split_once(separator).unwrap_or((mantissa, ""));
+        if (integer.is_empty() && fraction.is_empty())
+            || !integer
+                .chars()
+                .chain(fraction.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
+
+        let mut decimal = Self {
+            negative,
+            digits: integer
+                .bytes()
+                .chain(fraction.bytes())
+                .map(|digit| digit - b'0')
+                .collect(),
+            exponent: integer.len() as i32 - 1 + exponent,
+            approximate,
+        };
+        // Trailing zeros of a whole number are placeholders, not digits.
+        if fraction.is_empty() {
+            while decimal.digits.last() == Some(&0) {
+                decimal.digits.pop();
+            }
+        }
+        decimal.trim_leading_zeros();
+        Some(decimal)
+    }
+
+    fn trim_leading_zeros(&mut self) {
+        let zeros = self.digits.iter().take_while(|digit| **digit == 0).count();
+        if zeros == self.digits.len() {
+            self.digits.clear();
+            self.exponent = 0;
+        } else {
+            self.digits.drain(..zeros);
+            self.exponent -= zeros as i32;
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.digits.iter().all(|digit| *digit == 0)
+    }
+
+    /// Rounds so the last digit kept has the power `power`, padding with
+    /// zeros when there are fewer digits. Returns whether the value changed.
+    fn round_at(&mut self, power: i32, rounding: Rounding) -> bool {
+        if self.is_zero() {
+            self.digits = vec![0; (-power).max(0) as usize + 1];
+            self.exponent = 0;
+            return false;
+        }
+
+        let keep = self.exponent - power + 1;
+        if keep >= self.digits.len() as i32 {
+            self.digits.resize(keep as usize, 0);
+            return false;
+        }
+
+        let (first_dropped, dropped) = if keep >= 0 {
+            let dropped = &self.digits[keep as usize..];
+            (dropped[0], dropped)
+        } else {
+            (0, &self.digits[..])
+        };
+        let changed = dropped.iter().any(|digit| *digit != 0);
+        let last_kept = match keep {
+            1.. => self.digits[keep as usize - 1],
+            _ => 0,
+        };
+        let round_up = match rounding {
+            Rounding::HalfUp => first_dropped >= 5,
+            Rounding::HalfEven => {
+                let beyond_half = dropped.iter().skip(1).any(|digit| *digit != 0);
+                first_dropped > 5 || (first_dropped == 5 && (beyond_half || last_kept % 2 == 1))
+            }
+            Rounding::Truncate => false,
+            Rounding::Up => changed,
+        };
+
+        self.digits.truncate(keep.max(0) as usize);
+        if self.digits.is_empty() {
+            // Everything was dropped: the result is 0 or one unit at `power`.
+            self.digits.push(u8::from(round_up));
+            self.exponent = power;
+        } else if round_up {
+            let mut carry = true;
+            for digit in self.digits.iter_mut().rev() {
+                if *digit == 9 {
+                    *digit = 0;
+                } else {
+                    *digit += 1;
+                    carry = false;
+                    break;
+                }
+            }
+            if carry {
+                self.digits.insert(0, 1);
+                self.exponent += 1;
+            }
+        }
+        changed
+    }
+
+    /// Rounds to `figures` significant figures, keeping trailing zeros since
+    /// they are significant. Returns whether the value changed.
+    pub fn round_significant(&mut self, figures: u32, rounding: Rounding) -> bool {
+        let figures = figures.max(1) as usize;
+        if self.is_zero() {
+            return false;
+        }
+        let changed = self.round_at(self.exponent - figures as i32 + 1, rounding);
+        // A carry such as 9.99 to 10.0 adds a digit; the one dropped is 0.
+        self.digits.truncate(figures);
+        changed
+    }
+
+    /// Rounds to exactly `decimals` decimal places, padding with zeros.
+    /// Returns whether the value changed.
+    pub fn round_decimals(&mut self, decimals: u32, rounding: Rounding) -> bool {
+        self.round_at(-(decimals as i32), rounding)
+    }
+
+    /// Writes the number out in the notation `style` asks for.
+    pub fn format(&self, style: &Style) -> String {
+        let mut text = String::new();
+        if self.approximate {
+            text.push_str("≈ ");
+        }
+        if self.negative && !self.is_zero() {
+            text.push('-');
+        }
+        if self.digits.is_empty() {
+            text.push('0');
+            return text;
+        }
+
+        let notation = match style.notation {
+            Notation::Normal if self.exponent.unsigned_abs() >= style.threshold => {
+                Notation::Scientific
+            }
+            notation => notation,
+        };
+        // The power of ten factored out of the digits.
+        let power = match notation {
+            Notation::Normal => 0,
+            Notation::Scientific => self.exponent,
+            Notation::Engineering | Notation::Si => self.exponent.div_euclid(3) * 3,
+        };
+        text.push_str(&self.positional(self.exponent - power, style));
+
+        let prefix = SI_PREFIXES
+            .iter()
+            .find(|(exponent, _)| *exponent == power)
+            .filter(|_| notation == Notation::Si);
+        match prefix {
+            Some((_, "")) => {}
+            Some((_, prefix)) => {
+                text.push('\u{202F}');
+                text.push_str(prefix);
+            }
+            None if power != 0 || notation == Notation::Scientific => {
+                text.push_str("×10");
+                text.push_str(&superscript(power));
+            }
+            None => {}
+        }
+        text
+    }
+
+    /// The digits written out with the first one at the power `exponent`.
+    fn positional(&self, exponent: i32, style: &Style) -> String {
+        let last = exponent - self.digits.len() as i32 + 1;
+        let digit = |power: i32| {
+            let digit = usize::try_from(exponent - power)
+                .ok()
+                .and_then(|index| self.digits.get(index))
+                .copied()
+                .unwrap_or(0);
+            char::from(b'0' + digit)
+        };
+        let integer = (0..=exponent.max(0)).rev().map(digit).collect::<String>();
+        let mut text = group(&integer, style.grouping, style.decimal_comma);
+        if last < 0 {
+            text.push(if style.decimal_comma { ',' } else { '.' });
+            text.extend((last..0).rev().map(digit));
+        }
+        text
+    }
+}
+
+/// Inserts the separators of `grouping` between the digits of `integer`.
+fn group(integer: &str, grouping: DigitGrouping, decimal_comma: bool) -> String {
+    let separator = match grouping {
+        DigitGrouping::Off => return integer.to_string(),
+        DigitGrouping::ThinSpace => THIN_SPACES[0],
+        _ if decimal_comma => '.',
+        _ => ',',
+    };
+
+    // Built from the last digit backwards, since groups count from there.
+    let mut grouped = Vec::with_capacity(integer.len() * 4 / 3);
+    let mut size = 3;
+    let mut count = 0;
+    for digit in integer.chars().rev() {
+        if count == size {
+            grouped.push(separator);
+            count = 0;
+            if grouping == DigitGrouping::Indian {
+                size = 2;
+            }
+        }
+        grouped.push(digit);
+        count += 1;
+    }
+    grouped.into_iter().rev().collect()
+}
+
+/// Writes `exponent` in superscript digits, as in 10⁻³.
+pub fn superscript(exponent: i32) -> String {
+    exponent
+        .to_string()
+        .chars()
+        .map(|c| match c.to_digit(10) {
+            Some(digit) => SUPERSCRIPTS[digit as usize],
+            None => '⁻',
+        })
+        .collect()
+}
+
+/// The ASCII digit, or `-` for the minus, a superscript character stands for.
+pub fn superscript_digit(c: char) -> Option<char> {
+    match SUPERSCRIPTS.iter().position(|digit| *digit == c) {
+        Some(digit) => Some(char::from(b'0' + digit as u8)),
+        None => (c == '⁻').then_some('-'),
+    }
+}
+
+/// Rewrites the notations [`Decimal::format`] produces into ones qalc
+/// reads, so a result can be calculated with again: `1.2×10⁵` becomes
+/// `1.2×10^5`, `4.7 µ`, written with a narrow space, becomes `4.7E-6` and
+/// digit grouping such as `12,345` is removed. Meant for a single number;
+/// see [`plain_expression`] for what the user typed.
+pub fn plain(text: &str, decimal_comma: bool) -> String {
+    plain_notation(&ungroup(text, decimal_comma, true))
+}
+
+/// Like [`plain`], for an expression the user typed, where the comma in
+/// `max(1,000)` separates arguments. Only thin spaces are read as grouping
+/// there, besides within `results`, the numbers the app wrote into the
+/// expression itself.
+pub fn plain_expression(expression: &str, decimal_comma: bool, results: &[String]) -> String {
+    let mut expression = expression.to_string();
+    for result in results {
+        if let Some(start) = expression.find(result.as_str()) {
+            let plain = ungroup(result, decimal_comma, true);
+            expression.replace_range(start..start + result.len(), &plain);
+        }
+    }
+    plain_notation(&ungroup(&expression, decimal_comma, false))
+}
+
+/// Writes superscript exponents and SI prefixes the way qalc reads them.
+fn plain_notation(text: &str) -> String {
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut plain = String::with_capacity(text.len());
+    let mut index = 0;
+
+    while index < chars.len() {
+        let c = chars[index];
+        if superscript_digit(c).is_some() {
+            plain.push('^');
+            while let Some(digit) = chars.get(index).and_then(|c| superscript_digit(*c)) {
+                plain.push(digit);
+                index += 1;
+            }
+            continue;
+        }
+
+        if c == '\u{202F}'
+            && plain.ends_with(|c: char| c.is_ascii_digit())
+            && !chars.get(index + 2).is_some_and(|c| c.is_alphanumeric())
+            && let Some((exponent, _)) = chars.get(index + 1).and_then(|symbol| {
+                SI_PREFIXES
+                    .iter()
+                    .find(|(_, prefix)| prefix.chars().eq([*symbol]))
+            })
+        {
+            plain.push_str(&format!("E{exponent}"));
+            index += 2;
+            continue;
+        }
+
+        plain.push(c);
+        index += 1;
+    }
+    plain
+}
+
+/// Removes the separators from grouped numbers such as `12,345,678`,
+/// `1,23,45,678` or `12 345`, where `marks` says whether commas, or dots
+/// with a decimal comma, count as separators besides thin spaces. Only
+/// well-formed groups count. A number which is not grouped, or mixes
+/// separators, is left alone.
+fn ungroup(text: &str, decimal_comma: bool, marks: bool) -> String {
+    let mark = if decimal_comma { '.' } else { ',' };
+    let is_separator = |c: char| (marks && c == mark) || THIN_SPACES.contains(&c);
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut ungrouped = String::with_capacity(text.len());
+    let mut index = 0;
+
+    while index < chars.len() {
+        // Digits after a decimal point or inside a name such as `log10`
+        // aren't the start of a whole number.
+        let starts_number = chars[index].is_ascii_digit()
+            && (index == 0
+                || !(chars[index - 1].is_alphanumeric()
+                    || matches!(chars[index - 1], '_' | '.' | ',')));
+        if !starts_number {
+            ungrouped.push(chars[index]);
+            index += 1;
+            continue;
+        }
+
+        let start = index;
+        while index < chars.len()
+            && (chars[index].is_ascii_digit()
+                || (is_separator(chars[index])
+                    && chars.get(index + 1).is_some_and(char::is_ascii_digit)))
+        {
+            index += 1;
+        }
+        let number = &chars[start..index];
+        let separators = number
+            .iter()
+            .filter(|c| !c.is_ascii_digit())
+            .collect::<Vec<_>>();
+        let groups = number
+            .split(|c| !c.is_ascii_digit())
+            .map(<[char]>::len)
+            .collect::<Vec<_>>();
+        let middle = groups.get(1..groups.len() - 1).unwrap_or_default();
+        let grouped = !separators.is_empty()
+            && separators.iter().all(|c| *c == separators[0])
+            && (1..=3).contains(&groups[0])
+            && groups[groups.len() - 1] == 3
+            && (middle.iter().all(|size| *size == 3) || middle.iter().all(|size| *size == 2));
+        if grouped {
+            ungrouped.extend(number.iter().filter(|c| c.is_ascii_digit()));
+        } else {
+            ungrouped.extend(number);
+        }
+    }
+    ungrouped
+}
+
+/// The significant figures of the least precise number in `expression`, the
+/// usual rule for a product or quotient of measurements. Anything else, such
+/// as a sum, a power or a function, follows other rules and is `None`.
+/// `expression` is without grouping, as [`plain_expression`] leaves it.
+pub fn inferred_figures(expression: &str, decimal_comma: bool) -> Option<u32> {
+    let separator = if decimal_comma { ',' } else { '.' };
+    let chars = expression.chars().collect::<Vec<_>>();
+    let mut figures = None;
+    let mut index = 0;
+    // Whether an operand comes next, where a '-' or '+' is a sign.
+    let mut operand = true;
+
+    while index < chars.len() {
+        let c = chars[index];
+        let starts_number = c.is_ascii_digit()
+            || (c == separator && chars.get(index + 1).is_some_and(char::is_ascii_digit));
+        if !starts_number {
+            match c {
+                _ if c.is_whitespace() => {}
+                '*' | '×' | '/' | '÷' | '±' | '(' => operand = true,
+                '-' | '−' | '+' if operand => {}
+                ')' if !operand => {}
+                _ => return None,
+            }
+            index += 1;
+            continue;
+        }
+        if !operand {
+            return None;
+        }
+        operand = false;
+
+        let start = index;
+        while index < chars.len() && (chars[index].is_ascii_digit() || chars[index] == separator) {
+            index += 1;
+        }
+        let mantissa = chars[start..index].iter().collect::<String>();
+        // Skip an exponent such as `E-7`, which doesn't affect precision.
+        if index < chars.len() && matches!(chars[index], 'E' | 'e') {
+            let mut end = index + 1;
+            if end < chars.len() && matches!(chars[end], '+' | '-' | '−') {
+                end += 1;
+            }
+            if end < chars.len() && chars[end].is_ascii_digit() {
+                index = end;
+                while index < chars.len() && chars[index].is_ascii_digit() {
+                    index += 1;
+                }
+            }
+        }
+
+        // The uncertainty in `12.3 ± 0.2` says nothing about the precision.
+        let uncertainty = chars[..start]
+            .iter()
+            .rev()
+            .find(|c| !c.is_whitespace())
+            .is_some_and(|c| *c == '±');
+        if !uncertainty && let Some(count) = significant_figures(&mantissa, separator) {
+            figures = Some(figures.map_or(count, |figures: u32| figures.min(count)));
+        }
+    }
+    figures
+}
+
+/// Counts the significant figures of a literal such as `0.0120` (3) or
+/// `1200` (2). Zero has none.
+fn significant_figures(mantissa: &str, separator: char) -> Option<u32> {
+    let has_separator = mantissa.contains(separator);
+    let digits = mantissa.replace(separator, "");
+    let digits = digits.trim_start_matches('0');
+    // Trailing zeros of a whole number are placeholders, not measured.
+    let digits = if has_separator {
+        digits
+    } else {
+        digits.trim_end_matches('0')
+    };
+    (!digits.is_empty()).then(|| digits.len() as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAIN: Style = Style {
+        decimal_comma: false,
+        notation: Notation::Normal,
+        threshold: 12,
+        grouping: DigitGrouping::Off,
+    };
+
+    fn decimals(text: &str, decimals: u32, rounding: Rounding) -> String {
+        let mut decimal = Decimal::parse(text, false).unwrap();
+        decimal.round_decimals(decimals, rounding);
+        decimal.format(&PLAIN)
+    }
+
+    #[test]
+    fn half_up_rounds_ties_away_from_zero() {
+        assert_eq!(decimals("2.5", 0, Rounding::HalfUp), "3");
+        assert_eq!(decimals("-2.5", 0, Rounding::HalfUp), "-3");
+        assert_eq!(decimals("2.49", 0, Rounding::HalfUp), "2");
+        assert_eq!(decimals("-0.125", 2, Rounding::HalfUp), "-0.13");
+    }
+
+    #[test]
+    fn half_even_rounds_ties_to_the_even_digit() {
+        assert_eq!(decimals("2.5", 0, Rounding::HalfEven), "2");
+        assert_eq!(decimals("3.5", 0, Rounding::HalfEven), "4");
+        assert_eq!(decimals("-2.5", 0, Rounding::HalfEven), "-2");
+        assert_eq!(decimals("-3.5", 0, Rounding::HalfEven), "-4");
+        assert_eq!(decimals("2.501", 0, Rounding::HalfEven), "3");
+        assert_eq!(decimals("0.125", 2, Rounding::HalfEven), "0.12");
+    }
+
+    // Truncate and Up act on the magnitude, so a negative mirrors the
+    // positive rather than going to the floor or ceiling.
+    #[test]
+    fn truncate_rounds_towards_zero() {
+        assert_eq!(decimals("2.5", 0, Rounding::Truncate), "2");
+        assert_eq!(decimals("-2.5", 0, Rounding::Truncate), "-2");
+        assert_eq!(decimals("2.99", 1, Rounding::Truncate), "2.9");
+        assert_eq!(decimals("-2.99", 1, Rounding::Truncate), "-2.9");
+    }
+
+    #[test]
+    fn up_rounds_away_from_zero() {
+        assert_eq!(decimals("2.5", 0, Rounding::Up), "3");
+        assert_eq!(decimals("-2.5", 0, Rounding::Up), "-3");
+        assert_eq!(decimals("2.01", 1, Rounding::Up), "2.1");
+        assert_eq!(decimals("-2.01", 1, Rounding::Up), "-2.1");
+        assert_eq!(decimals("2", 1, Rounding::Up), "2.0");
+    }
+
+    #[test]
+    fn rounding_carries_into_new_digits() {
+        assert_eq!(decimals("9.995", 2, Rounding::HalfUp), "10.00");
+        assert_eq!(decimals("-9.995", 2, Rounding::HalfUp), "-10.00");
+        assert_eq!(decimals("9.995", 2, Rounding::HalfEven), "10.00");
+        assert_eq!(decimals("99.91", 0, Rounding::Up), "100");
+        assert_eq!(decimals("0.995", 2, Rounding::HalfUp), "1.00");
+        assert_eq!(decimals("9.999", 2, Rounding::Truncate), "9.99");
+    }
+
+    #[test]
+    fn rounding_below_the_last_decimal() {
+        assert_eq!(decimals("1.5", 4, Rounding::HalfUp), "1.5000");
+        assert_eq!(decimals("0.004", 2, Rounding::HalfUp), "0.00");
+        assert_eq!(decimals("-0.004", 2, Rounding::HalfUp), "0.00");
+        assert_eq!(decimals("0.005", 2, Rounding::HalfUp), "0.01");
+        assert_eq!(decimals("0.0001", 2, Rounding::Up), "0.01");
+        assert_eq!(decimals("1234.5", 0, Rounding::HalfEven), "1234");
+    }
+
+    fn significant(text: &str, figures: u32, notation: Notation) -> String {
+        let mut decimal = Decimal::parse(text, false).unwrap();
+        decimal.round_significant(figures, Rounding::HalfUp);
+        decimal.format(&Style { notation, ..PLAIN })
+    }
+
+    #[test]
+    fn significant_figures_carry_into_a_new_digit() {
+        assert_eq!(significant("999.5", 3, Notation::Scientific), "1.00×10³");
+        assert_eq!(significant("999.5", 3, Notation::Normal), "1000");
+        assert_eq!(significant("-999.5", 3, Notation::Scientific), "-1.00×10³");
+        assert_eq!(significant("9.96", 2, Notation::Normal), "10");
+    }
+
+    #[test]
+    fn significant_figures_of_negative_and_small_values() {
+        assert_eq!(significant("-0.0012345", 3, Notation::Normal), "-0.00123");
+        assert_eq!(significant("0.0009995", 3, Notation::Normal), "0.00100");
+        assert_eq!(significant("-123456", 2, Notation::Normal), "-120000");
+        assert_eq!(significant("0.5", 3, Notation::Normal), "0.500");
+    }
+
+    #[test]
+    fn significant_figures_report_whether_they_rounded() {
+        let mut decimal = Decimal::parse("1.20", false).unwrap();
+        assert!(!decimal.round_significant(3, Rounding::HalfUp));
+        let mut decimal = Decimal::parse("1.25", false).unwrap();
+        assert!(decimal.round_significant(2, Rounding::HalfUp));
+    }
+
+    #[test]
+    fn inferred_figures_follow_the_least_precise_factor() {
+        assert_eq!(inferred_figures("2.50*3.1", false), Some(2));
+        assert_eq!(inferred_figures("0.0120 / 5.000", false), Some(3));
+        assert_eq!(inferred_figures("-2.5*-4.00", false), Some(2));
+        assert_eq!(inferred_figures("1.20E-7×3.000", false), Some(3));
+        assert_eq!(inferred_figures("(12.3±0.2)*2.00", false), Some(3));
+        assert_eq!(inferred_figures("1,20*3,0", true), Some(2));
+    }
+
+    #[test]
+    fn inferred_figures_treat_trailing_zeros_of_whole_numbers_as_placeholders() {
+        assert_eq!(inferred_figures("1200*3.000", false), Some(2));
+        assert_eq!(inferred_figures("1200.*3.000", false), Some(4));
+        assert_eq!(inferred_figures("1200.0*3.00000", false), Some(5));
+        assert_eq!(inferred_figures("0*2.5", false), Some(2));
+    }
+
+    #[test]
+    fn inferred_figures_only_cover_products_and_quotients() {
+        assert_eq!(inferred_figures("100+25", false), None);
+        assert_eq!(inferred_figures("1.2345^2", false), None);
+        assert_eq!(inferred_figures("sqrt(2)", false), None);
+        assert_eq!(inferred_figures("2 pi", false), None);
+        assert_eq!(inferred_figures("3 4", false), None);
+    }
+
+    fn notation(text: &str, notation: Notation) -> String {
+        let decimal = Decimal::parse(text, false).unwrap();
+        decimal.format(&Style { notation, ..PLAIN })
+    }
+
+    #[test]
+    fn normal_notation_switches_at_the_threshold() {
+        assert_eq!(notation("999999999999", Notation::Normal), "999999999999");
+        assert_eq!(notation("1000000000000", Notation::Normal), "1×10¹²");
+        assert_eq!(notation("12000000000000", Notation::Normal), "1.2×10¹³");
+        assert_eq!(notation("0.00000000001", Notation::Normal), "0.00000000001");
+        assert_eq!(notation("0.000000000001", Notation::Normal), "1×10⁻¹²");
+        assert_eq!(
+            notation("-0.00000000000015", Notation::Normal),
+            "-1.5×10⁻¹³"
+        );
+        let style = Style {
+            threshold: 6,
+            ..PLAIN
+        };
+        let decimal = Decimal::parse("123456", false).unwrap();
+        assert_eq!(decimal.format(&style), "123456");
+        let decimal = Decimal::parse("1234567", false).unwrap();
+        assert_eq!(decimal.format(&style), "1.234567×10⁶");
+    }
+
+    #[test]
+    fn scientific_notation() {
+        assert_eq!(notation("123456", Notation::Scientific), "1.23456×10⁵");
+        assert_eq!(notation("-0.00047", Notation::Scientific), "-4.7×10⁻⁴");
+        assert_eq!(notation("5", Notation::Scientific), "5×10⁰");
+    }
+
+    #[test]
+    fn engineering_exponents_are_multiples_of_three() {
+        assert_eq!(notation("123456", Notation::Engineering), "123.456×10³");
+        assert_eq!(notation("1234", Notation::Engineering), "1.234×10³");
+        assert_eq!(notation("12", Notation::Engineering), "12");
+        assert_eq!(notation("0.0047", Notation::Engineering), "4.7×10⁻³");
+        assert_eq!(notation("-0.00012", Notation::Engineering), "-120×10⁻⁶");
+        assert_eq!(notation("0.00000000001", Notation::Engineering), "10×10⁻¹²");
+    }
+
+    #[test]
+    fn si_prefixes() {
+        assert_eq!(notation("123456", Notation::Si), "123.456\u{202F}k");
+        assert_eq!(notation("0.0000047", Notation::Si), "4.7\u{202F}µ");
+        assert_eq!(notation("42", Notation::Si), "42");
+        // Beyond quetta there is no prefix left.
+        assert_eq!(notation("1E33", Notation::Si), "1×10³³");
+    }
+
+    #[test]
+    fn notations_read_back_as_qalc_input() {
+        assert_eq!(plain_expression("1.2×10⁵", false, &[]), "1.2×10^5");
+        assert_eq!(plain_expression("1.2×10⁻⁷*2", false, &[]), "1.2×10^-7*2");
+        assert_eq!(plain_expression("4.7\u{202F}µ", false, &[]), "4.7E-6");
+        assert_eq!(plain_expression("x²", false, &[]), "x^2");
+        for text in ["123456", "-0.0047", "0.00000000001", "1E33"] {
+            let decimal = Decimal::parse(text, false).unwrap();
+            for notation in Notation::ALL {
+                let formatted = decimal.format(&Style { notation, ..PLAIN });
+                let plain = plain_expression(&formatted, false, &[]);
+                assert_eq!(
+                    Decimal::parse(&plain, false),
+                    Some(decimal.clone()),
+                    "{formatted}"
+                );
+            }
+        }
+    }
+
+    fn grouped(text: &str, grouping: DigitGrouping, decimal_comma: bool) -> String {
+        let decimal = Decimal::parse(text, decimal_comma).unwrap();
+        decimal.format(&Style {
+            decimal_comma,
+            grouping,
+            ..PLAIN
+        })
+    }
+
+    #[test]
+    fn grouping_in_thousands() {
+        let grouping = DigitGrouping::Thousands;
+        assert_eq!(grouped("12345678.9", grouping, false), "12,345,678.9");
+        assert_eq!(grouped("12345678,9", grouping, true), "12.345.678,9");
+        assert_eq!(grouped("-123456", grouping, false), "-123,456");
+        assert_eq!(grouped("999", grouping, false), "999");
+        assert_eq!(grouped("0.000123", grouping, false), "0.000123");
+        assert_eq!(
+            grouped("12345678.9", DigitGrouping::Off, false),
+            "12345678.9"
+        );
+    }
+
+    #[test]
+    fn indian_grouping_splits_lakhs_and_crores_in_twos() {
+        let grouping = DigitGrouping::Indian;
+        assert_eq!(grouped("12345678", grouping, false), "1,23,45,678");
+        assert_eq!(grouped("123456789.5", grouping, false), "12,34,56,789.5");
+        assert_eq!(grouped("12345", grouping, false), "12,345");
+        assert_eq!(grouped("1234567", grouping, true), "12.34.567");
+    }
+
+    #[test]
+    fn thin_space_grouping_keeps_the_decimal_separator() {
+        let grouping = DigitGrouping::ThinSpace;
+        assert_eq!(
+            grouped("1234567.5", grouping, false),
+            "1\u{2009}234\u{2009}567.5"
+        );
+        assert_eq!(
+            grouped("1234567,5", grouping, true),
+            "1\u{2009}234\u{2009}567,5"
+        );
+    }
+
+    #[test]
+    fn ungroup_strips_well_formed_groups() {
+        assert_eq!(plain("12,345,678.9", false), "12345678.9");
+        assert_eq!(plain("1,23,45,678", false), "12345678");
+        assert_eq!(plain("12.345.678,9", true), "12345678,9");
+        assert_eq!(plain("1\u{202F}234\u{202F}567", false), "1234567");
+        assert_eq!(plain("-1,234", false), "-1234");
+    }
+
+    #[test]
+    fn ungroup_leaves_anything_else_alone() {
+        // Malformed groups, mixed separators and decimals stay as typed.
+        assert_eq!(plain("1,2345", false), "1,2345");
+        assert_eq!(plain("12,34", false), "12,34");
+        assert_eq!(plain("1,234\u{2009}567", false), "1,234\u{2009}567");
+        assert_eq!(plain("0.123,456", false), "0.123,456");
+        assert_eq!(plain("12,345", true), "12,345");
+        assert_eq!(plain("log10(1,000)", false), "log10(1000)");
+    }
+
+    #[test]
+    fn pasted_expressions_keep_argument_commas() {
+        assert_eq!(plain_expression("gcd(12,345)", false, &[]), "gcd(12,345)");
+        assert_eq!(plain_expression("max(1,000)", false, &[]), "max(1,000)");
+        assert_eq!(
+            plain_expression("1\u{2009}234 + 5\u{202F}000", false, &[]),
+            "1234 + 5000"
+        );
+        assert_eq!(
+            plain_expression("1.234,5 * 2", true, &[String::from("1.234,5")]),
+            "1234,5 * 2"
+        );
+        assert_eq!(
+            plain_expression("max(12,345,678, 2)", false, &[String::from("12,345,678")]),
+            "max(12345678, 2)"
+        );
+    }
+}
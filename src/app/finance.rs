@@ -0,0 +1,667 @@
+use cosmic::{Element, iced::Length, widget};
+
+use crate::app::{
+    operations::{self, Calculator, EvaluateError},
+    tool::{self, Action},
+};
+use crate::fl;
+
+/// Upper bound on amortization rows (100 years of monthly payments).
+const MAX_PERIODS: usize = 1200;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Unknown {
+    N,
+    InterestRate,
+    PresentValue,
+    #[default]
+    Payment,
+    FutureValue,
+}
+
+impl Unknown {
+    const ALL: [Self; 5] = [
+        Self::N,
+        Self::InterestRate,
+        Self::PresentValue,
+        Self::Payment,
+        Self::FutureValue,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::N => "N",
+            Self::InterestRate => "I/Y",
+            Self::PresentValue => "PV",
+            Self::Payment => "PMT",
+            Self::FutureValue => "FV",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Solve(usize),
+    Field(Unknown, String),
+    PeriodsPerYear(String),
+    Begin(bool),
+    Calculate,
+    ExportCsv,
+}
+
+/// Time value of money inputs, using the cash flow sign convention of
+/// financial calculators: money received is positive, money paid negative.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tvm {
+    pub n: f64,
+    /// Nominal annual interest rate in percent.
+    pub interest_rate: f64,
+    pub present_value: f64,
+    pub payment: f64,
+    pub future_value: f64,
+    pub periods_per_year: f64,
+    /// Payments at the beginning of each period instead of the end.
+    pub begin: bool,
+}
+
+impl Tvm {
+    fn rate(&self) -> f64 {
+        self.interest_rate / 100.0 / self.periods_per_year
+    }
+
+    /// Residual of the TVM equation at periodic rate `i`; zero when balanced.
+    fn residual(&self, i: f64) -> f64 {
+        let timing = if self.begin { 1.0 + i } else { 1.0 };
+        if i.abs() < 1e-12 {
+            return self.present_value + self.payment * self.n + self.future_value;
+        }
+        let growth = (1.0 + i).powf(self.n);
+        self.present_value * growth + self.payment * timing * (growth - 1.0) / i + self.future_value
+    }
+
+    /// Fills in `unknown` from the other four values.
+    pub fn solve(mut self, unknown: Unknown) -> Option<Self> {
+        let i = self.rate();
+        let timing = if self.begin { 1.0 + i } else { 1.0 };
+        let growth = (1.0 + i).powf(self.n);
+        // Future value factor of the payment stream, (1+i)ⁿ−1 over i, a
+        // period more of interest when paying in advance.
+        let annuity = if i.abs() < 1e-12 {
+            self.n
+        } else {
+            timing * (growth - 1.0) / i
+        };
+
+        match unknown {
+            Unknown::PresentValue => {
+                self.present_value = -(self.payment * annuity + self.future_value) / growth;
+            }
+            Unknown::Payment => {
+                if annuity == 0.0 {
+                    return None;
+                }
+                self.payment = -(self.present_value * growth + self.future_value) / annuity;
+            }
+            Unknown::FutureValue => {
+                self.future_value = -(self.present_value * growth + self.payment * annuity);
+            }
+            Unknown::N => {
+                self.n = if i.abs() < 1e-12 {
+                    -(self.present_value + self.future_value) / self.payment
+                } else {
+                    let flow = self.payment * timing;
+                    ((flow - self.future_value * i) / (flow + self.present_value * i)).ln()
+                        / (1.0 + i).ln()
+                };
+            }
+            Unknown::InterestRate => {
+                let rate = self.solve_rate()?;
+                self.interest_rate = rate * 100.0 * self.periods_per_year;
+            }
+        }
+
+        [
+            self.n,
+            self.interest_rate,
+            self.present_value,
+            self.payment,
+            self.future_value,
+        ]
+        .iter()
+        .all(|value| value.is_finite())
+        .then_some(self)
+    }
+
+    /// Finds the periodic rate by bracketing a sign change and bisecting it.
+    fn solve_rate(&self) -> Option<f64> {
+        const STEPS: usize = 4000;
+        let rate_at = |step: usize| -0.99 + 2.0 * step as f64 / STEPS as f64;
+
+        let mut previous = (rate_at(0), self.residual(rate_at(0)));
+        for step in 1..=STEPS {
+            let current = (rate_at(step), self.residual(rate_at(step)));
+            if previous.1 == 0.0 {
+                return Some(previous.0);
+            }
+            if previous.1.signum() != current.1.signum() && current.1.is_finite() {
+                let (mut low, mut high) = (previous.0, current.0);
+                for _ in 0..200 {
+                    let middle = (low + high) / 2.0;
+                    if self.residual(middle).signum() == self.residual(low).signum() {
+                        low = middle;
+                    } else {
+                        high = middle;
+                    }
+                }
+                return Some((low + high) / 2.0);
+            }
+            previous = current;
+        }
+        None
+    }
+
+    /// Splits each payment into interest and principal until the balance is
+    /// paid. A fractional N ends with a partial period, whose payment leaves
+    /// exactly the future value.
+    pub fn schedule(&self) -> Vec<Period> {
+        let i = self.rate();
+        // N solved from the other values is rarely a whole number exactly.
+        let n = if (self.n - self.n.round()).abs() < 1e-9 {
+            self.n.round()
+        } else {
+            self.n
+        };
+        let periods = (n.max(0.0).floor() as usize).min(MAX_PERIODS);
+        let mut balance = self.present_value;
+        let mut schedule = Vec::with_capacity(periods + 1);
+
+        for period in 1..=periods {
+            let start = balance;
+            let interest = if self.begin {
+                (balance + self.payment) * i
+            } else {
+                balance * i
+            };
+            balance += interest + self.payment;
+            schedule.push(Period {
+                period,
+                payment: self.payment,
+                interest,
+                principal: start - balance,
+                balance,
+            });
+        }
+
+        let fraction = n - n.floor();
+        if fraction > 0.0 && periods < MAX_PERIODS {
+            let start = balance;
+            let growth = (1.0 + i).powf(fraction);
+            let payment = if self.begin {
+                -self.future_value / growth - start
+            } else {
+                -self.future_value - start * growth
+            };
+            let interest = if self.begin {
+                (start + payment) * (growth - 1.0)
+            } else {
+                start * (growth - 1.0)
+            };
+            balance = start + interest + payment;
+            schedule.push(Period {
+                period: periods + 1,
+                payment,
+                interest,
+                principal: start - balance,
+                balance,
+            });
+        }
+        schedule
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Period {
+    pub period: usize,
+    pub payment: f64,
+    pub interest: f64,
+    pub principal: f64,
+    pub balance: f64,
+}
+
+pub struct Finance {
+    solve: Unknown,
+    unknowns: Vec<String>,
+    fields: [String; 5],
+    periods_per_year: String,
+    begin: bool,
+    schedule: Vec<Period>,
+}
+
+impl Default for Finance {
+    fn default() -> Self {
+        Self {
+            solve: Unknown::default(),
+            unknowns: Unknown::ALL.iter().map(|u| u.label().to_string()).collect(),
+            fields: Default::default(),
+            periods_per_year: String::from("12"),
+            begin: false,
+            schedule: vec![],
+        }
+    }
+}
+
+impl Finance {
+    fn index(unknown: Unknown) -> usize {
+        Unknown::ALL.iter().position(|u| u == &unknown).unwrap_or(0)
+    }
+
+    pub fn update(&mut self, message: Message, decimal_comma: bool) -> Action<Message> {
+        match message {
+            Message::Solve(index) => self.solve = Unknown::ALL[index],
+            Message::Field(unknown, value) => self.fields[Self::index(unknown)] = value,
+            Message::PeriodsPerYear(value) => self.periods_per_year = value,
+            Message::Begin(begin) => self.begin = begin,
+            Message::Calculate => return self.calculate(decimal_comma),
+            Message::ExportCsv => {
+                if !self.schedule.is_empty() {
+                    return Action::Export("amortization", "csv", self.to_csv(decimal_comma));
+                }
+            }
+        }
+        Action::None
+    }
+
+    fn calculate(&mut self, decimal_comma: bool) -> Action<Message> {
+        let parse = |text: &str| {
+            if text.trim().is_empty() {
+                Some(0.0)
+            } else {
+                operations::parse_number(text, decimal_comma)
+            }
+        };
+        let values = self
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                if index == Self::index(self.solve) {
+                    Some(0.0)
+                } else {
+                    parse(field)
+                }
+            })
+            .collect::<Option<Vec<f64>>>();
+        let (Some(values), Some(periods_per_year)) = (
+            values,
+            operations::parse_number(&self.periods_per_year, decimal_comma)
+                .filter(|periods| *periods > 0.0),
+        ) else {
+            return Action::Error(EvaluateError::InvalidInput);
+        };
+
+        let tvm = Tvm {
+            n: values[0],
+            interest_rate: values[1],
+            present_value: values[2],
+            payment: values[3],
+            future_value: values[4],
+            periods_per_year,
+            begin: self.begin,
+        };
+        let Some(solved) = tvm.solve(self.solve) else {
+            return Action::Error(EvaluateError::InvalidInput);
+        };
+
+        let solved_values = [
+            solved.n,
+            solved.interest_rate,
+            solved.present_value,
+            solved.payment,
+            solved.future_value,
+        ];
+        let result = format_value(
+            self.solve,
+            solved_values[Self::index(self.solve)],
+            decimal_comma,
+        );
+        self.fields[Self::index(self.solve)] = result.clone();
+        self.schedule = solved.schedule();
+
+        let knowns = Unknown::ALL
+            .iter()
+            .zip(solved_values)
+            .filter(|(unknown, _)| **unknown != self.solve)
+            .map(|(unknown, value)| {
+                format!(
+                    "{}={}",
+                    unknown.label(),
+                    format_value(*unknown, value, decimal_comma)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        Action::History(Calculator {
+            expression: format!("{} ← {knowns}", self.solve.label()),
+            outcome: result,
+            decimal_comma,
+            details: vec![format!(
+                "P/Y={} {}",
+                self.periods_per_year.trim(),
+                if self.begin { "BGN" } else { "END" }
+            )],
+            unrounded: None,
+        })
+    }
+
+    fn to_csv(&self, decimal_comma: bool) -> String {
+        let mut csv = String::from("period,payment,interest,principal,balance\n");
+        for period in &self.schedule {
+            let fields = [
+                period.period.to_string(),
+                operations::format_amount(period.payment, decimal_comma),
+                operations::format_amount(period.interest, decimal_comma),
+                operations::format_amount(period.principal, decimal_comma),
+                operations::format_amount(period.balance, decimal_comma),
+            ];
+            let line = fields
+                .iter()
+                .map(|field| tool::csv_field(field))
+                .collect::<Vec<_>>()
+                .join(",");
+            csv.push_str(&line);
+            csv.push('\n');
+        }
+        csv
+    }
+
+    pub fn view(&self, decimal_comma: bool) -> Element<'_, Message> {
+        let spacing = cosmic::theme::active().cosmic().spacing;
+
+        let mut inputs = widget::settings::section().add(widget::settings::item(
+            fl!("solve-for"),
+            widget::dropdown(
+                &self.unknowns,
+                Some(Self::index(self.solve)),
+                Message::Solve,
+            ),
+        ));
+        for (unknown, value) in Unknown::ALL.iter().zip(&self.fields) {
+            let unknown = *unknown;
+            let input = widget::text_input("", value).on_submit(|_| Message::Calculate);
+            // The solved field only shows the result, so it stays read-only.
+            let input = if unknown == self.solve {
+                input
+            } else {
+                input.on_input(move |value| Message::Field(unknown, value))
+            };
+            inputs = inputs.add(widget::settings::item(unknown.label(), input));
+        }
+        inputs = inputs
+            .add(widget::settings::item(
+                "P/Y",
+                widget::text_input("12", &self.periods_per_year)
+                    .on_input(Message::PeriodsPerYear)
+                    .on_submit(|_| Message::Calculate),
+            ))
+            .add(
+                widget::settings::item::builder(fl!("payments-at-beginning"))
+                    .toggler(self.begin, Message::Begin),
+            );
+
+        let controls = widget::row::with_capacity(2)
+            .push(widget::button::suggested(fl!("calculate")).on_press(Message::Calculate))
+            .push(
+                widget::button::standard(fl!("export-csv"))
+                    .on_press_maybe((!self.schedule.is_empty()).then_some(Message::ExportCsv)),
+            )
+            .spacing(spacing.space_xxs);
+
+        let headers = [
+            "#".to_string(),
+            fl!("payment"),
+            fl!("interest"),
+            fl!("principal"),
+            fl!("balance"),
+        ];
+        let mut table = widget::column::with_capacity(self.schedule.len() + 1)
+            .push(
+                widget::row::with_children(
+                    headers
+                        .into_iter()
+                        .map(|header| cell(header, true))
+                        .collect(),
+                )
+                .spacing(spacing.space_xs),
+            )
+            .spacing(spacing.space_xxxs);
+        for period in &self.schedule {
+            table = table.push(
+                widget::row::with_children(vec![
+                    cell(period.period.to_string(), false),
+                    cell(
+                        operations::format_amount(period.payment, decimal_comma),
+                        false,
+                    ),
+                    cell(
+                        operations::format_amount(period.interest, decimal_comma),
+                        false,
+                    ),
+                    cell(
+                        operations::format_amount(period.principal, decimal_comma),
+                        false,
+                    ),
+                    cell(
+                        operations::format_amount(period.balance, decimal_comma),
+                        false,
+                    ),
+                ])
+                .spacing(spacing.space_xs),
+            );
+        }
+
+        widget::settings::view_column(vec![
+            inputs.into(),
+            controls.into(),
+            widget::scrollable(table)
+                .height(Length::Fixed(320.0))
+                .into(),
+        ])
+        .into()
+    }
+}
+
+fn cell(text: String, header: bool) -> Element<'static, Message> {
+    let text = if header {
+        widget::text::heading(text)
+    } else {
+        widget::text::body(text)
+    };
+    text.width(Length::FillPortion(1)).into()
+}
+
+/// Like [`operations::format_amount`], keeping four decimals for interest rates.
+fn format_value(unknown: Unknown, value: f64, decimal_comma: bool) -> String {
+    if unknown != Unknown::InterestRate {
+        return operations::format_amount(value, decimal_comma);
+    }
+    let text = format!("{value:.4}");
+    if decimal_comma {
+        text.replace('.', ",")
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 30-year mortgage of 200,000 at 6% paid monthly.
+    const MORTGAGE: Tvm = Tvm {
+        n: 360.0,
+        interest_rate: 6.0,
+        present_value: 200_000.0,
+        payment: -1_199.101_050_305_513_8,
+        future_value: 0.0,
+        periods_per_year: 12.0,
+        begin: false,
+    };
+
+    fn close(actual: f64, expected: f64) -> bool {
+        (actual - expected).abs() <= 1e-6 * expected.abs().max(1.0)
+    }
+
+    #[test]
+    fn solves_each_unknown_of_a_mortgage() {
+        let solved = Tvm { n: 0.0, ..MORTGAGE }.solve(Unknown::N).unwrap();
+        assert!(close(solved.n, 360.0), "{}", solved.n);
+        let solved = Tvm {
+            interest_rate: 0.0,
+            ..MORTGAGE
+        }
+        .solve(Unknown::InterestRate)
+        .unwrap();
+        assert!(close(solved.interest_rate, 6.0), "{}", solved.interest_rate);
+        let solved = Tvm {
+            present_value: 0.0,
+            ..MORTGAGE
+        }
+        .solve(Unknown::PresentValue)
+        .unwrap();
+        assert!(
+            close(solved.present_value, 200_000.0),
+            "{}",
+            solved.present_value
+        );
+        let solved = Tvm {
+            payment: 0.0,
+            ..MORTGAGE
+        }
+        .solve(Unknown::Payment)
+        .unwrap();
+        assert!(
+            close(solved.payment, MORTGAGE.payment),
+            "{}",
+            solved.payment
+        );
+        let solved = Tvm {
+            future_value: 1.0,
+            ..MORTGAGE
+        }
+        .solve(Unknown::FutureValue)
+        .unwrap();
+        assert!(close(solved.future_value, 0.0), "{}", solved.future_value);
+    }
+
+    #[test]
+    fn solves_the_future_value_of_savings() {
+        let savings = Tvm {
+            n: 120.0,
+            interest_rate: 5.0,
+            present_value: -1_000.0,
+            payment: -100.0,
+            future_value: 0.0,
+            periods_per_year: 12.0,
+            begin: false,
+        };
+        let solved = savings.solve(Unknown::FutureValue).unwrap();
+        assert!(
+            close(solved.future_value, 17_175.237_442_257),
+            "{}",
+            solved.future_value
+        );
+    }
+
+    #[test]
+    fn payments_in_advance() {
+        // A lease of 30,000 with a 10,000 residual over three years at 8%.
+        let lease = Tvm {
+            n: 36.0,
+            interest_rate: 8.0,
+            present_value: 30_000.0,
+            payment: 0.0,
+            future_value: -10_000.0,
+            periods_per_year: 12.0,
+            begin: true,
+        };
+        let solved = lease.solve(Unknown::Payment).unwrap();
+        assert!(
+            close(solved.payment, -688.801_962_809_89),
+            "{}",
+            solved.payment
+        );
+        let last = solved.schedule().last().copied().unwrap();
+        assert!(close(last.balance, 10_000.0), "{}", last.balance);
+    }
+
+    #[test]
+    fn zero_interest() {
+        let loan = Tvm {
+            n: 12.0,
+            interest_rate: 0.0,
+            present_value: 1_200.0,
+            payment: 0.0,
+            future_value: 0.0,
+            periods_per_year: 12.0,
+            begin: false,
+        };
+        let solved = loan.solve(Unknown::Payment).unwrap();
+        assert!(close(solved.payment, -100.0), "{}", solved.payment);
+        let solved = Tvm { n: 0.0, ..solved }.solve(Unknown::N).unwrap();
+        assert!(close(solved.n, 12.0), "{}", solved.n);
+    }
+
+    #[test]
+    fn rate_without_a_sign_change_is_not_solved() {
+        // Every cash flow is received, so no rate balances them.
+        let tvm = Tvm {
+            n: 10.0,
+            interest_rate: 0.0,
+            present_value: 100.0,
+            payment: 10.0,
+            future_value: 10.0,
+            periods_per_year: 1.0,
+            begin: false,
+        };
+        assert_eq!(tvm.solve_rate(), None);
+        assert_eq!(tvm.solve(Unknown::InterestRate), None);
+    }
+
+    #[test]
+    fn schedule_pays_off_the_loan() {
+        let schedule = MORTGAGE.schedule();
+        assert_eq!(schedule.len(), 360);
+        assert!(
+            close(schedule[0].interest, 1_000.0),
+            "{}",
+            schedule[0].interest
+        );
+        assert!(schedule.last().unwrap().balance.abs() < 1e-6);
+    }
+
+    #[test]
+    fn fractional_n_ends_with_a_partial_period() {
+        let loan = Tvm {
+            n: 0.0,
+            interest_rate: 12.0,
+            present_value: 1_000.0,
+            payment: -300.0,
+            future_value: 0.0,
+            periods_per_year: 12.0,
+            begin: false,
+        };
+        let solved = loan.solve(Unknown::N).unwrap();
+        assert!(close(solved.n, 3.407_077_832_481_065_5), "{}", solved.n);
+        let schedule = solved.schedule();
+        assert_eq!(schedule.len(), 4);
+        assert_eq!(schedule[2].payment, -300.0);
+        let last = schedule[3];
+        assert!(
+            last.payment > -300.0 && last.payment < 0.0,
+            "{}",
+            last.payment
+        );
+        assert!(last.balance.abs() < 1e-9, "{}", last.balance);
+    }
+}
@@ -0,0 +1,257 @@
+use cosmic::{
+    Element,
+    iced::{Alignment, Length},
+    widget,
+};
+
+use crate::app::{display, tool::Action};
+use crate::fl;
+
+const ROMAN: [(u32, &str); 13] = [
+    (1000, "M"),
+    (900, "CM"),
+    (500, "D"),
+    (400, "CD"),
+    (100, "C"),
+    (90, "XC"),
+    (50, "L"),
+    (40, "XL"),
+    (10, "X"),
+    (9, "IX"),
+    (5, "V"),
+    (4, "IV"),
+    (1, "I"),
+];
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Input(String),
+    Copy(String),
+    Insert(String),
+}
+
+#[derive(Default)]
+pub struct Formats {
+    input: String,
+}
+
+impl Formats {
+    pub fn update(&mut self, message: Message) -> Action<Message> {
+        match message {
+            Message::Input(input) => self.input = input,
+            Message::Copy(text) => return Action::Copy(text),
+            Message::Insert(value) => return Action::Insert(value),
+        }
+        Action::None
+    }
+
+    /// Shows `outcome` in the alternate formats, followed by a converter for
+    /// typed numbers and Roman numerals.
+    pub fn view(&self, outcome: &str, decimal_comma: bool) -> Element<'_, Message> {
+        let spacing = cosmic::theme::active().cosmic().spacing;
+
+        let output = |label: String, value: Option<String>, insert: Option<String>| {
+            let mut row = widget::row::with_capacity(3)
+                .push(
+                    widget::text::body(value.clone().unwrap_or_else(|| String::from("—")))
+                        .width(Length::Fill),
+                )
+                .push(
+                    widget::button::standard(fl!("copy")).on_press_maybe(value.map(Message::Copy)),
+                )
+                .spacing(spacing.space_xxs)
+                .align_y(Alignment::Center);
+            if let Some(insert) = insert {
+                row = row.push(
+                    widget::button::standard(fl!("insert")).on_press(Message::Insert(insert)),
+                );
+            }
+            widget::settings::item(label, row)
+        };
+
+        // Digit grouping would stop the outcome from reading as a number.
+        let number = display::plain(outcome, decimal_comma);
+        let integer = number.trim().parse::<u32>().ok();
+        let result = widget::settings::section()
+            .title(fl!("result"))
+            .add(widget::settings::item(
+                fl!("value"),
+                widget::text::heading(outcome.to_string()),
+            ))
+            .add(output(
+                fl!("roman-numeral"),
+                integer.and_then(to_roman),
+                None,
+            ))
+            .add(output(fl!("in-words"), words(&number, decimal_comma), None));
+
+        let mut convert = widget::settings::section().title(fl!("convert")).add(
+            widget::text_input(fl!("convert-placeholder"), &self.input).on_input(Message::Input),
+        );
+        let input = display::plain(&self.input, decimal_comma);
+        let input = input.trim();
+        if let Some(number) = from_roman(input) {
+            convert = convert.add(output(
+                fl!("value"),
+                Some(number.to_string()),
+                Some(number.to_string()),
+            ));
+        } else if !input.is_empty() {
+            let integer = input.parse::<u32>().ok();
+            convert = convert
+                .add(output(
+                    fl!("roman-numeral"),
+                    integer.and_then(to_roman),
+                    None,
+                ))
+                .add(output(fl!("in-words"), words(input, decimal_comma), None));
+        }
+
+        let mut column = Vec::with_capacity(3);
+        if !outcome.is_empty() {
+            column.push(result.into());
+        }
+        column.push(convert.into());
+        column.push(widget::text::caption(fl!("roman-range")).into());
+        widget::settings::view_column(column).into()
+    }
+}
+
+/// Writes `number` as a Roman numeral; only 1 to 3999 have one.
+pub fn to_roman(mut number: u32) -> Option<String> {
+    if !(1..=3999).contains(&number) {
+        return None;
+    }
+    let mut roman = String::new();
+    for (value, symbol) in ROMAN {
+        while number >= value {
+            roman.push_str(symbol);
+            number -= value;
+        }
+    }
+    Some(roman)
+}
+
+/// Reads a Roman numeral in either case. Only the canonical spelling is
+/// accepted, so `IIII` and `IC` are rejected rather than guessed at.
+pub fn from_roman(text: &str) -> Option<u32> {
+    let text = text.trim().to_uppercase();
+    let mut number = 0;
+    let mut rest = text.as_str();
+    for (value, symbol) in ROMAN {
+        while let Some(stripped) = rest.strip_prefix(symbol) {
+            number += value;
+            rest = stripped;
+        }
+    }
+    (rest.is_empty() && number > 0)
+        .then_some(number)
+        .filter(|number| to_roman(*number).as_deref() == Some(text.as_str()))
+}
+
+/// Spells out a plain decimal number such as `-1234` or `12.05` in the
+/// active language, taking any piece its translation lacks from English.
+/// Integers up to the largest `u64` are supported, with decimals read digit
+/// by digit.
+pub fn words(number: &str, decimal_comma: bool) -> Option<String> {
+    let ones = plain(&fl!("number-words-ones"));
+    let ones = ones.split_whitespace().collect::<Vec<_>>();
+    let tens = plain(&fl!("number-words-tens"));
+    let tens = tens.split_whitespace().collect::<Vec<_>>();
+    if ones.len() != 20 || tens.len() != 8 {
+        return None;
+    }
+
+    let number = number.trim().replace('−', "-");
+    let (negative, number) = match number.strip_prefix('-') {
+        Some(number) => (true, number),
+        None => (false, number.as_str()),
+    };
+    let separator = if decimal_comma { ',' } else { '.' };
+    let (integer, decimals) = number.split_once(separator).unwrap_or((number, ""));
+    if integer.is_empty() || !decimals.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let integer = integer.parse::<u64>().ok()?;
+
+    let mut words = integer_words(integer, &ones, &tens);
+    if !decimals.is_empty() {
+        let digits = decimals
+            .bytes()
+            .map(|digit| ones[usize::from(digit - b'0')])
+            .collect::<Vec<_>>()
+            .join(" ");
+        words = plain(&fl!(
+            "number-words-decimal",
+            integer = words,
+            digits = digits
+        ));
+    }
+    if negative && (integer != 0 || decimals.bytes().any(|digit| digit != b'0')) {
+        words = plain(&fl!("number-words-negative", words = words));
+    }
+    Some(words)
+}
+
+fn integer_words(mut number: u64, ones: &[&str], tens: &[&str]) -> String {
+    if number == 0 {
+        return ones[0].to_string();
+    }
+
+    // Groups of three digits, lowest first, with their scale.
+    let mut groups = vec![];
+    let mut scale = 0;
+    while number > 0 {
+        if number % 1000 > 0 {
+            groups.push((scale, number % 1000));
+        }
+        number /= 1000;
+        scale += 1;
+    }
+
+    groups
+        .into_iter()
+        .rev()
+        .map(|(scale, group)| {
+            let words = below_thousand(group, ones, tens);
+            if scale == 0 {
+                words
+            } else {
+                plain(&fl!("number-words-scale", words = words, scale = scale))
+            }
+        })
+        .reduce(|high, low| plain(&fl!("number-words-compound", high = high, low = low)))
+        .unwrap_or_default()
+}
+
+fn below_thousand(number: u64, ones: &[&str], tens: &[&str]) -> String {
+    let (hundreds, rest) = (number / 100, (number % 100) as usize);
+    let rest = match rest {
+        0 => None,
+        1..=19 => Some(ones[rest].to_string()),
+        _ if rest % 10 == 0 => Some(tens[rest / 10 - 2].to_string()),
+        _ => Some(plain(&fl!(
+            "number-words-tens-units",
+            tens = tens[rest / 10 - 2],
+            units = ones[rest % 10]
+        ))),
+    };
+    let hundreds = (hundreds > 0).then(|| {
+        plain(&fl!(
+            "number-words-hundreds",
+            units = ones[hundreds as usize]
+        ))
+    });
+
+    match (hundreds, rest) {
+        (Some(high), Some(low)) => plain(&fl!("number-words-compound", high = high, low = low)),
+        (Some(words), None) | (None, Some(words)) => words,
+        (None, None) => String::new(),
+    }
+}
+
+/// Drops the Unicode isolation marks Fluent wraps around arguments, which
+/// would otherwise pile up inside nested phrases and copied text.
+fn plain(text: &str) -> String {
+    text.replace(['\u{2068}', '\u{2069}'], "")
+}
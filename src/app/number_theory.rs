@@ -0,0 +1,656 @@
+use std::time::{Duration, Instant};
+
+use cosmic::{
+    Element,
+    iced::{Alignment, Length, Task},
+    widget,
+};
+
+use crate::app::{operations::EvaluateError, tool::Action};
+use crate::fl;
+
+/// Below this bound Miller-Rabin with the first thirteen prime bases is
+/// exact; above it a pass only means "probable prime".
+const DETERMINISTIC_BOUND: u128 = 3_317_044_064_679_887_385_961_981;
+const BASES: [u128; 13] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
+/// Time a whole factorization may spend in rho, across every cofactor and
+/// seed, before the rest is reported unfactored. Each press runs to
+/// completion, so this keeps hard numbers from tying up a worker.
+const RHO_TIME: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Operation {
+    #[default]
+    Factorize,
+    Primality,
+    GcdLcm,
+    ModularInverse,
+    ModularPower,
+}
+
+impl Operation {
+    const ALL: [Self; 5] = [
+        Self::Factorize,
+        Self::Primality,
+        Self::GcdLcm,
+        Self::ModularInverse,
+        Self::ModularPower,
+    ];
+
+    fn label(&self) -> String {
+        match self {
+            Self::Factorize => fl!("factorize"),
+            Self::Primality => fl!("primality"),
+            Self::GcdLcm => fl!("gcd-lcm"),
+            Self::ModularInverse => fl!("modular-inverse"),
+            Self::ModularPower => fl!("modular-power"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Operation(usize),
+    Number(String),
+    Exponent(String),
+    Modulus(String),
+    Calculate,
+    Calculated(u64, Vec<Output>),
+    Insert(usize),
+}
+
+/// One result line, with the form inserted into the expression.
+#[derive(Debug, Clone)]
+pub struct Output {
+    label: String,
+    value: String,
+    expression: Option<String>,
+}
+
+pub struct NumberTheory {
+    operation: Operation,
+    operations: Vec<String>,
+    number: String,
+    exponent: String,
+    modulus: String,
+    outputs: Vec<Output>,
+    generation: u64,
+}
+
+impl Default for NumberTheory {
+    fn default() -> Self {
+        Self {
+            operation: Operation::default(),
+            operations: Operation::ALL.iter().map(Operation::label).collect(),
+            number: String::new(),
+            exponent: String::new(),
+            modulus: String::new(),
+            outputs: vec![],
+            generation: 0,
+        }
+    }
+}
+
+impl NumberTheory {
+    pub fn update(&mut self, message: Message) -> Action<Message> {
+        match message {
+            Message::Operation(index) => {
+                self.operation = Operation::ALL[index];
+                self.outputs.clear();
+            }
+            Message::Number(number) => self.number = number,
+            Message::Exponent(exponent) => self.exponent = exponent,
+            Message::Modulus(modulus) => self.modulus = modulus,
+            Message::Calculate => return self.calculate(),
+            Message::Calculated(generation, outputs) => {
+                if generation == self.generation {
+                    self.outputs = outputs;
+                }
+            }
+            Message::Insert(index) => {
+                if let Some(expression) = self
+                    .outputs
+                    .get(index)
+                    .and_then(|output| output.expression.clone())
+                {
+                    return Action::Insert(expression);
+                }
+            }
+        }
+        Action::None
+    }
+
+    fn calculate(&mut self) -> Action<Message> {
+        let outputs = match self.operation {
+            Operation::Factorize | Operation::Primality => {
+                let Some(n) = parse_integer(&self.number) else {
+                    return Action::Error(EvaluateError::InvalidInput);
+                };
+                let operation = self.operation;
+                self.generation += 1;
+                let generation = self.generation;
+                // Factoring can take a while, so keep it off the UI thread.
+                return Task::perform(
+                    async move {
+                        match operation {
+                            Operation::Factorize => factorize_outputs(n),
+                            _ => primality_outputs(n),
+                        }
+                    },
+                    move |outputs| Message::Calculated(generation, outputs),
+                )
+                .into();
+            }
+            Operation::GcdLcm => {
+                let numbers = self
+                    .number
+                    .split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+                    .filter(|number| !number.is_empty())
+                    .map(parse_integer)
+                    .collect::<Option<Vec<_>>>();
+                let Some(numbers) = numbers.filter(|numbers| !numbers.is_empty()) else {
+                    return Action::Error(EvaluateError::InvalidInput);
+                };
+                let numbers = numbers.iter().map(|n| n.unsigned_abs()).collect::<Vec<_>>();
+                let divisor = numbers.iter().fold(0, |acc, n| gcd(acc, *n));
+                let multiple = numbers.iter().try_fold(1u128, |acc, n| lcm(acc, *n));
+                vec![
+                    Output::number(fl!("gcd"), divisor),
+                    match multiple {
+                        Some(multiple) => Output::number(fl!("lcm"), multiple),
+                        None => Output::text(fl!("lcm"), fl!("overflow")),
+                    },
+                ]
+            }
+            Operation::ModularInverse => {
+                let (Some(a), Some(m)) = (
+                    parse_integer(&self.number),
+                    parse_integer(&self.modulus).filter(|m| *m > 1),
+                ) else {
+                    return Action::Error(EvaluateError::InvalidInput);
+                };
+                let m = m.unsigned_abs();
+                vec![match modular_inverse(reduce(a, m), m) {
+                    Some(inverse) => Output::number(fl!("result"), inverse),
+                    None => Output::text(fl!("result"), fl!("no-inverse")),
+                }]
+            }
+            Operation::ModularPower => {
+                let (Some(a), Some(e), Some(m)) = (
+                    parse_integer(&self.number),
+                    parse_integer(&self.exponent),
+                    parse_integer(&self.modulus).filter(|m| *m > 0),
+                ) else {
+                    return Action::Error(EvaluateError::InvalidInput);
+                };
+                let m = m.unsigned_abs();
+                let base = reduce(a, m);
+                // A negative exponent raises the inverse instead.
+                let base = if e < 0 {
+                    match modular_inverse(base, m) {
+                        Some(inverse) => inverse,
+                        None => {
+                            self.outputs = vec![Output::text(fl!("result"), fl!("no-inverse"))];
+                            return Action::None;
+                        }
+                    }
+                } else {
+                    base
+                };
+                vec![Output::number(
+                    fl!("result"),
+                    pow_mod(base, e.unsigned_abs(), m),
+                )]
+            }
+        };
+        self.generation += 1;
+        self.outputs = outputs;
+        Action::None
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let spacing = cosmic::theme::active().cosmic().spacing;
+
+        let number_label = match self.operation {
+            Operation::GcdLcm => fl!("numbers"),
+            Operation::ModularInverse | Operation::ModularPower => String::from("a"),
+            Operation::Factorize | Operation::Primality => String::from("n"),
+        };
+        let mut inputs = widget::settings::section()
+            .add(widget::settings::item(
+                fl!("operation"),
+                widget::dropdown(
+                    &self.operations,
+                    Operation::ALL
+                        .iter()
+                        .position(|operation| *operation == self.operation),
+                    Message::Operation,
+                ),
+            ))
+            .add(widget::settings::item(
+                number_label,
+                widget::text_input("", &self.number)
+                    .on_input(Message::Number)
+                    .on_submit(|_| Message::Calculate),
+            ));
+        if self.operation == Operation::ModularPower {
+            inputs = inputs.add(widget::settings::item(
+                fl!("exponent"),
+                widget::text_input("", &self.exponent)
+                    .on_input(Message::Exponent)
+                    .on_submit(|_| Message::Calculate),
+            ));
+        }
+        if matches!(
+            self.operation,
+            Operation::ModularInverse | Operation::ModularPower
+        ) {
+            inputs = inputs.add(widget::settings::item(
+                fl!("modulus"),
+                widget::text_input("", &self.modulus)
+                    .on_input(Message::Modulus)
+                    .on_submit(|_| Message::Calculate),
+            ));
+        }
+
+        let mut results = widget::settings::section().title(fl!("result"));
+        for (index, output) in self.outputs.iter().enumerate() {
+            results = results.add(widget::settings::item(
+                output.label.as_str(),
+                widget::row::with_capacity(2)
+                    .push(widget::text::body(output.value.as_str()))
+                    .push(
+                        widget::button::standard(fl!("insert")).on_press_maybe(
+                            output
+                                .expression
+                                .is_some()
+                                .then_some(Message::Insert(index)),
+                        ),
+                    )
+                    .spacing(spacing.space_xxs)
+                    .align_y(Alignment::Center),
+            ));
+        }
+
+        let mut column = vec![
+            inputs.into(),
+            widget::button::suggested(fl!("calculate"))
+                .on_press(Message::Calculate)
+                .into(),
+        ];
+        if !self.outputs.is_empty() {
+            column.push(results.into());
+        }
+        widget::settings::view_column(column)
+            .width(Length::Fill)
+            .into()
+    }
+}
+
+impl Output {
+    fn number(label: String, value: u128) -> Self {
+        Self {
+            label,
+            value: value.to_string(),
+            expression: Some(value.to_string()),
+        }
+    }
+
+    fn text(label: String, value: String) -> Self {
+        Self {
+            label,
+            value,
+            expression: None,
+        }
+    }
+}
+
+fn factorize_outputs(n: i128) -> Vec<Output> {
+    let magnitude = n.unsigned_abs();
+    if magnitude < 2 {
+        return vec![Output::number(fl!("factors"), magnitude)];
+    }
+
+    let (factors, unfactored) = factorize(magnitude, Instant::now() + RHO_TIME);
+    let mut display = factors
+        .iter()
+        .map(|(prime, exponent)| match exponent {
+            1 => prime.to_string(),
+            _ => format!("{prime}^{exponent}"),
+        })
+        .collect::<Vec<_>>();
+    if let Some(cofactor) = unfactored {
+        display.push(cofactor.to_string());
+    }
+    if n < 0 {
+        display.insert(0, String::from("-1"));
+    }
+
+    let mut outputs = vec![Output {
+        label: fl!("factors"),
+        value: display.join(" × "),
+        expression: Some(display.join("*")),
+    }];
+    if let Some(cofactor) = unfactored {
+        outputs.push(Output::text(
+            fl!("unfactored"),
+            fl!("composite", value = cofactor.to_string()),
+        ));
+    }
+    outputs
+}
+
+fn primality_outputs(n: i128) -> Vec<Output> {
+    let n = n.unsigned_abs();
+    let verdict = if n < 2 {
+        fl!("not-prime")
+    } else if !is_probable_prime(n) {
+        match smallest_factor(n) {
+            Some(factor) => fl!("composite-divisible", value = factor.to_string()),
+            None => fl!("not-prime"),
+        }
+    } else if n < DETERMINISTIC_BOUND {
+        fl!("prime")
+    } else {
+        fl!("probable-prime")
+    };
+    vec![Output::text(n.to_string(), verdict)]
+}
+
+/// Parses an integer, ignoring digit group separators such as `_` or spaces.
+fn parse_integer(text: &str) -> Option<i128> {
+    let text = text
+        .trim()
+        .replace(['_', ' ', '\u{2009}', '\u{202f}'], "")
+        .replace('−', "-");
+    text.parse().ok()
+}
+
+/// `a` modulo `m`, in `0..m` even for negative `a`.
+fn reduce(a: i128, m: u128) -> u128 {
+    let r = a.unsigned_abs() % m;
+    if a < 0 && r != 0 { m - r } else { r }
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple, or `None` when it overflows 128 bits.
+fn lcm(a: u128, b: u128) -> Option<u128> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// The inverse of `a` modulo `m`, if `a` and `m` are coprime.
+fn modular_inverse(a: u128, m: u128) -> Option<u128> {
+    // Extended Euclid, tracking the coefficient of `a` modulo `m`.
+    let (mut r0, mut r1) = (m, a % m);
+    let (mut t0, mut t1) = (0u128, 1u128);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (t0, t1) = (t1, sub_mod(t0, mul_mod(q % m, t1, m), m));
+    }
+    (r0 == 1).then_some(t0 % m)
+}
+
+fn add_mod(a: u128, b: u128, m: u128) -> u128 {
+    if a >= m - b { a - (m - b) } else { a + b }
+}
+
+fn sub_mod(a: u128, b: u128, m: u128) -> u128 {
+    if a >= b { a - b } else { m - (b - a) }
+}
+
+/// `a * b mod m` without overflowing, for any 128-bit modulus.
+fn mul_mod(a: u128, b: u128, m: u128) -> u128 {
+    if let Some(product) = a.checked_mul(b) {
+        return product % m;
+    }
+    let (mut a, mut b, mut result) = (a % m, b % m, 0);
+    while b > 0 {
+        if b & 1 == 1 {
+            result = add_mod(result, a, m);
+        }
+        a = add_mod(a, a, m);
+        b >>= 1;
+    }
+    result
+}
+
+fn pow_mod(mut base: u128, mut exponent: u128, m: u128) -> u128 {
+    if m == 1 {
+        return 0;
+    }
+    let mut result = 1;
+    base %= m;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exponent >>= 1;
+    }
+    result
+}
+
+/// Miller-Rabin; exact below [`DETERMINISTIC_BOUND`].
+fn is_probable_prime(n: u128) -> bool {
+    if n < 2 {
+        return false;
+    }
+    for base in BASES {
+        if n % base == 0 {
+            return n == base;
+        }
+    }
+
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    'bases: for base in BASES {
+        let mut x = pow_mod(base, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                continue 'bases;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+/// Prime factors of `n` with their exponents, in ascending order, plus any
+/// composite cofactor left when rho runs past `deadline`.
+fn factorize(mut n: u128, deadline: Instant) -> (Vec<(u128, u32)>, Option<u128>) {
+    let mut primes = vec![];
+    for p in (2..1000u128).filter(|p| is_probable_prime(*p)) {
+        while n % p == 0 {
+            primes.push(p);
+            n /= p;
+        }
+    }
+
+    let mut unfactored = None;
+    let mut pending = if n > 1 { vec![n] } else { vec![] };
+    while let Some(n) = pending.pop() {
+        if is_probable_prime(n) {
+            primes.push(n);
+        } else if let Some(divisor) = rho(n, deadline) {
+            pending.push(divisor);
+            pending.push(n / divisor);
+        } else {
+            unfactored = Some(unfactored.map_or(n, |cofactor: u128| cofactor * n));
+        }
+    }
+
+    primes.sort_unstable();
+    let mut factors: Vec<(u128, u32)> = vec![];
+    for prime in primes {
+        match factors.last_mut() {
+            Some((last, exponent)) if *last == prime => *exponent += 1,
+            _ => factors.push((prime, 1)),
+        }
+    }
+    (factors, unfactored)
+}
+
+/// The smallest prime factor found by [`factorize`], if any.
+fn smallest_factor(n: u128) -> Option<u128> {
+    factorize(n, Instant::now() + RHO_TIME)
+        .0
+        .first()
+        .map(|(prime, _)| *prime)
+}
+
+/// Brent's variant of Pollard's rho; finds a nontrivial divisor of the odd
+/// composite `n`, or gives up at `deadline`.
+fn rho(n: u128, deadline: Instant) -> Option<u128> {
+    const BATCH: u32 = 128;
+
+    for c in 1..8u128 {
+        let f = |x: u128| add_mod(mul_mod(x, x, n), c, n);
+        let (mut y, mut r, mut q) = (2u128, 1u32, 1u128);
+        let (mut x, mut ys) = (y, y);
+        let mut divisor = 1;
+
+        while divisor == 1 {
+            x = y;
+            for _ in 0..r {
+                y = f(y);
+            }
+            let mut k = 0;
+            while k < r && divisor == 1 {
+                if Instant::now() >= deadline {
+                    return None;
+                }
+                ys = y;
+                for _ in 0..BATCH.min(r - k) {
+                    y = f(y);
+                    q = mul_mod(q, x.abs_diff(y), n);
+                }
+                divisor = gcd(q, n);
+                k += BATCH;
+            }
+            r *= 2;
+        }
+
+        // The batch overshot; step back one at a time.
+        if divisor == n {
+            loop {
+                ys = f(ys);
+                divisor = gcd(x.abs_diff(ys), n);
+                if divisor > 1 {
+                    break;
+                }
+            }
+        }
+        if divisor > 1 && divisor < n {
+            return Some(divisor);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn carmichael_numbers_are_composite() {
+        for n in [561, 1105, 1729, 2465, 2821, 6601, 8911] {
+            assert!(!is_probable_prime(n), "{n}");
+        }
+        // A strong pseudoprime to every prime base up to 23.
+        assert!(!is_probable_prime(3_825_123_056_546_413_051));
+    }
+
+    #[test]
+    fn primality_around_the_deterministic_bound() {
+        assert!(is_probable_prime(DETERMINISTIC_BOUND - 168));
+        assert!(is_probable_prime(DETERMINISTIC_BOUND + 142));
+        // The bound is the first composite every base lets through.
+        assert_eq!(DETERMINISTIC_BOUND, 1_287_836_182_261 * 2_575_672_364_521);
+        assert!(is_probable_prime(DETERMINISTIC_BOUND));
+    }
+
+    #[test]
+    fn primality_near_the_largest_u128() {
+        assert!(is_probable_prime(u128::MAX - 158));
+        assert!(!is_probable_prime(u128::MAX - 157));
+        assert!(!is_probable_prime(u128::MAX));
+    }
+
+    #[test]
+    fn small_numbers() {
+        assert!(!is_probable_prime(0));
+        assert!(!is_probable_prime(1));
+        assert!(is_probable_prime(2));
+        assert!(is_probable_prime(41));
+        assert!(!is_probable_prime(41 * 43));
+    }
+
+    #[test]
+    fn factorize_finds_every_prime() {
+        assert_eq!(
+            factorize(360, Instant::now() + RHO_TIME),
+            (vec![(2, 3), (3, 2), (5, 1)], None)
+        );
+        assert_eq!(
+            factorize(1_000_003 * 1_000_033, Instant::now() + RHO_TIME),
+            (vec![(1_000_003, 1), (1_000_033, 1)], None)
+        );
+        assert_eq!(
+            factorize(u128::MAX, Instant::now() + RHO_TIME),
+            (
+                vec![
+                    (3, 1),
+                    (5, 1),
+                    (17, 1),
+                    (257, 1),
+                    (641, 1),
+                    (65_537, 1),
+                    (274_177, 1),
+                    (6_700_417, 1),
+                    (67_280_421_310_721, 1),
+                ],
+                None
+            )
+        );
+    }
+
+    #[test]
+    fn factorize_gives_up_at_the_deadline() {
+        let n = 1_000_000_007 * 1_000_000_009;
+        assert_eq!(factorize(2 * n, Instant::now()), (vec![(2, 1)], Some(n)));
+    }
+
+    #[test]
+    fn modular_inverses() {
+        assert_eq!(modular_inverse(3, 11), Some(4));
+        assert_eq!(modular_inverse(10, 17), Some(12));
+        assert_eq!(modular_inverse(2, u128::MAX), Some(1 << 127));
+        assert_eq!(modular_inverse(6, 9), None);
+        assert_eq!(modular_inverse(0, 7), None);
+    }
+
+    #[test]
+    fn modular_powers() {
+        assert_eq!(pow_mod(2, 10, 1000), 24);
+        assert_eq!(pow_mod(7, 0, 13), 1);
+        assert_eq!(pow_mod(7, 5, 1), 0);
+        // Fermat's little theorem with a modulus that needs the slow product.
+        let p = u128::MAX - 158;
+        assert_eq!(pow_mod(3, p - 1, p), 1);
+        assert_eq!(pow_mod(u128::MAX - 1, 2, u128::MAX), 1);
+    }
+}
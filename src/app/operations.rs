@@ -1,8 +1,13 @@
-use crate::app::operator::Operator;
+use crate::app::{
+    config::{DigitGrouping, PercentMode},
+    display,
+    operator::Operator,
+};
 use semver::Version;
 use serde::{Deserialize, Serialize};
 use std::{
     fmt::Display,
+    io::Write,
     process::{Command, Stdio},
 };
 
@@ -11,6 +16,15 @@ pub struct Calculator {
     pub expression: String,
     pub outcome: String,
     pub decimal_comma: bool,
+    /// Extra result lines shown under the display, e.g. a numeric form.
+    #[serde(default)]
+    pub details: Vec<String>,
+    /// The outcome as qalc printed it, before it was rounded and formatted
+    /// for display, so a reused result is rounded once rather than twice.
+    /// Tool results leave it empty, since their outcome is stored as
+    /// computed; either is rounded with the current settings when reused.
+    #[serde(default)]
+    pub unrounded: Option<String>,
 }
 
 impl Display for Calculator {
@@ -21,6 +35,40 @@ impl Display for Calculator {
 
 pub enum Message {
     Evaluate,
+    AddTax,
+    RemoveTax,
+    RecallGrandTotal,
+    ClearGrandTotal,
+    AddToMemory,
+    SubtractFromMemory,
+    RecallMemory,
+    ClearMemory,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EvaluateError {
+    /// The expression was empty, which would drop qalc into interactive mode.
+    Empty,
+    /// A tool was given fields it can't compute with.
+    InvalidInput,
+    /// qalc could not be spawned.
+    Spawn,
+    /// qalc ran but printed nothing usable.
+    Parse,
+    /// qalc reported an error on stderr.
+    Qalc(String),
+}
+
+impl Display for EvaluateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "Nothing to evaluate"),
+            Self::InvalidInput => write!(f, "Invalid input"),
+            Self::Spawn => write!(f, "Failed to execute qalc command"),
+            Self::Parse => write!(f, "Failed to parse qalc output"),
+            Self::Qalc(_) => write!(f, "An error occurred"),
+        }
+    }
 }
 
 impl Calculator {
@@ -43,6 +91,7 @@ impl Calculator {
             | Operator::Multiply
             | Operator::Divide
             | Operator::Modulus
+            | Operator::Percent
             | Operator::Point
             | Operator::ParenthesesOpen
             | Operator::ParenthesesClose
@@ -52,32 +101,81 @@ impl Calculator {
             Operator::Clear => self.clear(),
             Operator::Negate => self.toggle_sign(),
             Operator::Equal => return Some(Message::Evaluate),
+            Operator::TaxPlus => return Some(Message::AddTax),
+            Operator::TaxMinus => return Some(Message::RemoveTax),
+            Operator::GrandTotal => return Some(Message::RecallGrandTotal),
+            Operator::ClearGrandTotal => return Some(Message::ClearGrandTotal),
+            Operator::MemoryAdd => return Some(Message::AddToMemory),
+            Operator::MemorySubtract => return Some(Message::SubtractFromMemory),
+            Operator::MemoryRecall => return Some(Message::RecallMemory),
+            Operator::MemoryClear => return Some(Message::ClearMemory),
             Operator::Backspace => {
                 self.expression.pop();
             }
+            // Stack keys only act in RPN mode.
+            Operator::Enter
+            | Operator::Swap
+            | Operator::Roll
+            | Operator::Drop
+            | Operator::LastX => {}
         };
         None
     }
-    pub fn toggle_sign(&mut self) {
-        // Start index of the trailing number, if the expression ends with one.
-        let Some(num_start) = self
-            .expression
-            .char_indices()
-            .rev()
-            .take_while(|(_, c)| c.is_ascii_digit() || *c == '.' || *c == ',')
-            .last()
-            .map(|(i, _)| i)
+
+    /// Swaps a trailing binary operator for `operator`, as chain mode does when
+    /// operators are pressed back to back. Returns false if there was none.
+    pub fn replace_trailing_operator(&mut self, operator: &Operator) -> bool {
+        let trimmed = self.expression.trim_end();
+        let Some(previous) = Operator::BINARY
+            .iter()
+            .map(|previous| previous.expression().trim())
+            .find(|previous| trimmed.ends_with(previous))
         else {
+            return false;
+        };
+        let end = trimmed.len() - previous.len();
+        self.expression = self.expression[..end].trim_end().to_string();
+        self.add_operator(operator.clone());
+        true
+    }
+
+    /// Appends a value from a tool, parenthesizing negatives so they can
+    /// follow an operator.
+    pub fn insert(&mut self, value: &str) {
+        if value.starts_with('-') && !self.expression.is_empty() {
+            self.expression.push_str(&format!("({value})"));
+        } else {
+            self.expression.push_str(value);
+        }
+        self.details.clear();
+    }
+
+    pub fn toggle_sign(&mut self) {
+        let Some(mut num_start) = trailing_number(&self.expression) else {
             return; // empty or not ending in a number: nothing to negate
         };
+        // In `12.3 ± 0.2` the sign belongs to the measured value, not to its
+        // uncertainty, so negate the number before the '±'.
+        if let Some(value) = self.expression[..num_start].trim_end().strip_suffix('±') {
+            let Some(value_start) = trailing_number(value.trim_end()) else {
+                return;
+            };
+            num_start = value_start;
+        }
 
         let before = &self.expression[..num_start];
-        // A '-' is unary at the start or right after an operator or '('.
-        let is_unary_minus = before.ends_with('-')
-            && matches!(
-                before[..before.len() - 1].chars().next_back(),
-                None | Some('+' | '-' | '*' | '/' | '×' | '÷' | '%' | '^' | '(')
-            );
+        // A '-' is unary at the start or right after an operator, an operator
+        // word such as `mod`, or '('.
+        let is_unary_minus = before.strip_suffix('-').is_some_and(|operand| {
+            let operand = operand.trim_end();
+            operand.is_empty()
+                || operand.ends_with(['+', '-', '*', '/', '×', '÷', '%', '^', '('])
+                || OPERATOR_WORDS.iter().any(|word| {
+                    operand.strip_suffix(word).is_some_and(|rest| {
+                        rest.is_empty() || rest.ends_with(|c: char| c.is_whitespace() || c == ')')
+                    })
+                })
+        });
 
         if is_unary_minus {
             self.expression.remove(num_start - 1);
@@ -89,37 +187,478 @@ impl Calculator {
     pub fn clear(&mut self) {
         self.expression.clear();
         self.outcome = String::new();
+        self.unrounded = None;
+        self.details.clear();
     }
 
     pub(crate) fn on_input(&mut self, input: String) {
-        // qalc validates the expression itself, so keep this filter permissive:
-        // allow letters (sin, pi), whitespace, '!', and ',' for decimal-comma locales.
-        if input.chars().all(|c| {
-            c.is_alphanumeric()
-                || c.is_whitespace()
-                || matches!(
-                    c,
-                    '+' | '-'
-                        | '*'
-                        | '/'
-                        | '÷'
-                        | '×'
-                        | '%'
-                        | '.'
-                        | ','
-                        | '('
-                        | ')'
-                        | '^'
-                        | '√'
-                        | '!'
-                        | '\u{8}'
-                )
-        }) {
+        if input.chars().all(is_expression_char) {
             self.expression = input;
         }
     }
 }
 
+/// Operators qalc spells as words, written between spaces.
+const OPERATOR_WORDS: [&str; 5] = ["mod", "rem", "and", "or", "xor"];
+
+/// Start index of the number `text` ends with, if any, digit grouping included.
+fn trailing_number(text: &str) -> Option<usize> {
+    text.char_indices()
+        .rev()
+        .take_while(|(_, c)| {
+            c.is_ascii_digit() || matches!(c, '.' | ',') || display::THIN_SPACES.contains(c)
+        })
+        .last()
+        .map(|(i, _)| i)
+}
+
+/// Characters accepted in typed or pasted expressions.
+///
+/// qalc validates the expression itself, so keep this filter permissive:
+/// allow letters (sin, pi), whitespace, '!', ',' for decimal-comma locales, and
+/// '±' for measurements with an uncertainty such as `12.3 ± 0.2`.
+pub fn is_expression_char(c: char) -> bool {
+    c.is_alphanumeric()
+        || c.is_whitespace()
+        || matches!(
+            c,
+            '+' | '-'
+                | '*'
+                | '/'
+                | '÷'
+                | '×'
+                | '%'
+                | '.'
+                | ','
+                | '('
+                | ')'
+                | '^'
+                | '√'
+                | '!'
+                | '±'
+                | '\u{8}'
+        )
+}
+
+/// Rewrites `expression` for the percent mode: desk semantics, or `%`
+/// passed to qalc unchanged.
+pub fn apply_percent_mode(expression: &str, percent_mode: PercentMode) -> String {
+    match percent_mode {
+        PercentMode::Desk => desk_percent(expression),
+        PercentMode::Literal => expression.to_string(),
+    }
+}
+
+/// Rewrites `%` with desk calculator semantics before handing the expression to qalc.
+///
+/// `a + b%` and `a - b%` add or remove b percent of `a`, where `a` is everything
+/// before the operator in the same parenthesis group. `a * b%` takes b percent of
+/// `a` and `a / b%` divides by it. A bare `b%` is left for qalc to read as b/100.
+pub fn desk_percent(expression: &str) -> String {
+    let mut chars = expression.chars().collect::<Vec<_>>();
+    let mut position = 0;
+
+    while let Some(offset) = chars[position..].iter().position(|c| *c == '%') {
+        let percent = position + offset;
+        position = percent + 1;
+
+        // The percentage is the number or parenthesized group before '%'.
+        let mut end = percent;
+        while end > 0 && chars[end - 1].is_whitespace() {
+            end -= 1;
+        }
+        let start = if end > 0 && chars[end - 1] == ')' {
+            let Some(open) = matching_open(&chars, end - 1) else {
+                continue;
+            };
+            open
+        } else {
+            let mut start = end;
+            while start > 0
+                && (chars[start - 1].is_ascii_digit() || matches!(chars[start - 1], '.' | ','))
+            {
+                start -= 1;
+            }
+            start
+        };
+        if start == end {
+            continue;
+        }
+
+        let mut operator = start;
+        while operator > 0 && chars[operator - 1].is_whitespace() {
+            operator -= 1;
+        }
+        let Some(operator) = operator.checked_sub(1) else {
+            continue;
+        };
+        let percentage = chars[start..end].iter().collect::<String>();
+
+        let (from, replacement) = match chars[operator] {
+            '*' | '×' | '/' | '÷' => (start, format!("({percentage}/100)")),
+            sign @ ('+' | '-' | '−') => {
+                let mut depth = 0;
+                let mut group = 0;
+                for index in (0..operator).rev() {
+                    match chars[index] {
+                        ')' => depth += 1,
+                        '(' if depth == 0 => {
+                            group = index + 1;
+                            break;
+                        }
+                        '(' => depth -= 1,
+                        _ => {}
+                    }
+                }
+                let base = chars[group..operator].iter().collect::<String>();
+                let base = base.trim();
+                // A sign with nothing before it is unary, as in `-10%` or `2*-10%`.
+                if base.is_empty() || base.ends_with(['+', '-', '−', '*', '×', '/', '÷', '^', '('])
+                {
+                    continue;
+                }
+                let sign = if sign == '+' { '+' } else { '-' };
+                (group, format!("({base})*(1{sign}{percentage}/100)"))
+            }
+            _ => continue,
+        };
+
+        position = from + replacement.chars().count();
+        chars.splice(from..=percent, replacement.chars());
+    }
+
+    chars.into_iter().collect()
+}
+
+/// Returns the last top-level operation of `expression`, such as `+3` in
+/// `5+3`, so a repeated `=` can apply it again to the result.
+pub fn last_operation(expression: &str) -> Option<String> {
+    let chars = expression.chars().collect::<Vec<_>>();
+    let mut depth = 0;
+
+    for index in (0..chars.len()).rev() {
+        match chars[index] {
+            ')' => depth += 1,
+            '(' => depth -= 1,
+            ' ' if depth == 0 && chars[index..].starts_with(&[' ', 'm', 'o', 'd', ' ']) => {
+                let operand = chars[index + 5..].iter().collect::<String>();
+                return (!operand.trim().is_empty()).then(|| format!(" mod {}", operand.trim()));
+            }
+            operator @ ('+' | '-' | '−' | '*' | '×' | '/' | '÷' | '^') if depth == 0 => {
+                let before = chars[..index].iter().collect::<String>();
+                let before = before.trim_end();
+                // Skip unary signs, as in `-5` or `2*-3`, and exponents like `1.2E-7`.
+                let unary = before.is_empty()
+                    || before.ends_with(['+', '-', '−', '*', '×', '/', '÷', '^', '(']);
+                let exponent = matches!(operator, '+' | '-')
+                    && before.ends_with(['E', 'e'])
+                    && before[..before.len() - 1].ends_with(|c: char| c.is_ascii_digit());
+                if unary || exponent {
+                    continue;
+                }
+                let operand = chars[index + 1..].iter().collect::<String>();
+                return (!operand.trim().is_empty())
+                    .then(|| format!("{operator}{}", operand.trim()));
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Index of the '(' that matches the ')' at `close`.
+fn matching_open(chars: &[char], close: usize) -> Option<usize> {
+    let mut depth = 0;
+    for index in (0..=close).rev() {
+        match chars[index] {
+            ')' => depth += 1,
+            '(' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(index);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Evaluates `expression` with qalc and returns its terse result.
+pub fn evaluate(expression: &str, decimal_comma: bool) -> Result<String, EvaluateError> {
+    evaluate_with(expression, decimal_comma, &[])
+}
+
+/// Like [`evaluate`], applying extra qalc `-set` options first.
+pub fn evaluate_with(
+    expression: &str,
+    decimal_comma: bool,
+    settings: &[&str],
+) -> Result<String, EvaluateError> {
+    // An empty expression drops qalc into interactive mode, which hangs.
+    let expression = expression.trim();
+    if expression.is_empty() {
+        return Err(EvaluateError::Empty);
+    }
+
+    let mut command = qalc_command(decimal_comma, settings, expression.contains('±'));
+    // Never let qalc block waiting on stdin.
+    command.stdin(Stdio::null());
+    command.arg(expression);
+
+    let Ok(output) = command.output() else {
+        return Err(EvaluateError::Spawn);
+    };
+
+    let outcome = String::from_utf8(output.stdout)
+        .unwrap_or_default()
+        .replace(['\n', '\r'], "")
+        // Strip any stray interactive-prompt artifact.
+        .replace("> ", "")
+        .trim()
+        .to_string();
+
+    // qalc writes warnings to stderr even on success, so only treat the
+    // run as failed when stdout has no usable result.
+    if outcome.is_empty() {
+        let error = String::from_utf8(output.stderr).unwrap_or_default();
+        if error.is_empty() {
+            return Err(EvaluateError::Parse);
+        }
+        return Err(EvaluateError::Qalc(error));
+    }
+
+    Ok(outcome)
+}
+
+/// Evaluated after each expression in a batch, so the output can be split
+/// back into results even when an expression fails and prints nothing. qalc
+/// prints an unknown name back symbolically, which no numeric result can
+/// equal.
+const BATCH_SENTINEL: &str = "cosmic_calculator_batch_end";
+
+/// Evaluates many expressions with a single qalc process, one per input line.
+///
+/// Spawning qalc per expression is far too slow for plots and tables, so the
+/// expressions are piped through stdin, each followed by [`BATCH_SENTINEL`].
+/// The sentinel also comes first, so its output is known exactly before any
+/// result is read. An expression qalc can't evaluate leaves nothing before
+/// its sentinel and fails on its own. Only if the sentinels themselves don't
+/// line up is each expression evaluated separately instead.
+pub fn evaluate_batch(
+    expressions: &[String],
+    decimal_comma: bool,
+    settings: &[&str],
+) -> Vec<Result<String, EvaluateError>> {
+    if expressions.is_empty() {
+        return vec![];
+    }
+
+    let uncertain = expressions
+        .iter()
+        .any(|expression| expression.contains('±'));
+    let mut command = qalc_command(decimal_comma, settings, uncertain);
+    command.stdin(Stdio::piped());
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::null());
+
+    let results = command.spawn().ok().and_then(|mut child| {
+        let mut input = format!("{BATCH_SENTINEL}\n");
+        for expression in expressions {
+            input.push_str(&expression.replace(['\n', '\r'], " "));
+            input.push('\n');
+            input.push_str(BATCH_SENTINEL);
+            input.push('\n');
+        }
+        // Dropping stdin after writing closes it so qalc exits.
+        child.stdin.take()?.write_all(input.as_bytes()).ok()?;
+        let output = child.wait_with_output().ok()?;
+        let output = String::from_utf8(output.stdout).ok()?;
+        split_batch(&output, expressions.len(), decimal_comma)
+    });
+
+    match results {
+        Some(results) => results
+            .into_iter()
+            .zip(expressions)
+            .map(|(outcome, expression)| {
+                if expression.trim().is_empty() {
+                    Err(EvaluateError::Empty)
+                } else if outcome.is_empty() {
+                    Err(EvaluateError::Parse)
+                } else {
+                    Ok(outcome)
+                }
+            })
+            .collect(),
+        None => expressions
+            .iter()
+            .map(|expression| evaluate_with(expression, decimal_comma, settings))
+            .collect(),
+    }
+}
+
+/// Splits the output of [`evaluate_batch`] into `count` results at the
+/// sentinels, or `None` if they don't line up.
+fn split_batch(output: &str, count: usize, decimal_comma: bool) -> Option<Vec<String>> {
+    let mut lines = output
+        .lines()
+        .map(|line| line.replace("> ", "").trim().to_string())
+        .skip_while(String::is_empty);
+    // How this qalc prints the sentinel, which must not read as a number.
+    let marker = lines
+        .next()
+        .filter(|marker| parse_number(marker, decimal_comma).is_none())?;
+
+    let mut results = vec![];
+    let mut outcome = String::new();
+    for line in lines {
+        if line == marker {
+            results.push(std::mem::take(&mut outcome));
+        } else {
+            outcome.push_str(&line);
+        }
+    }
+    (results.len() == count).then_some(results)
+}
+
+/// Builds a qalc invocation. With `uncertain` set, values written as `a±b`
+/// are propagated to first order through operators and functions (qalc's
+/// variance formula) and results are shown as value ± uncertainty.
+fn qalc_command(decimal_comma: bool, settings: &[&str], uncertain: bool) -> Command {
+    let mut command = Command::new("qalc");
+    command.env("LANG", "C");
+    command.args(["-t"]);
+    command.args(["-u8"]);
+    command.args(["-set", "maxdeci 9"]);
+
+    if decimal_comma {
+        command.args(["-set", "decimal comma on"]);
+    } else {
+        command.args(["-set", "decimal comma off"]);
+    }
+
+    if autocalc() {
+        command.args(["-set", "autocalc off"]);
+    }
+
+    if uncertain {
+        command.args(["-set", "interval calculation variance"]);
+        command.args(["-set", "interval display plusminus"]);
+    }
+
+    for setting in settings {
+        command.args(["-set", setting]);
+    }
+
+    command
+}
+
+/// Replaces every standalone `variable` identifier in `expression` with `value`.
+///
+/// Identifiers are matched whole, so substituting `x` leaves `exp` and `max` alone,
+/// while `2x` still becomes `2(value)`.
+pub fn substitute(expression: &str, variable: &str, value: &str) -> String {
+    let mut result = String::with_capacity(expression.len());
+    let mut chars = expression.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c.is_alphabetic() || c == '_' {
+            let mut identifier = String::from(c);
+            while let Some(&next) = chars.peek() {
+                if next.is_alphanumeric() || next == '_' {
+                    identifier.push(next);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            if identifier == variable {
+                result.push('(');
+                result.push_str(value);
+                result.push(')');
+            } else {
+                result.push_str(&identifier);
+            }
+        } else {
+            result.push(c);
+        }
+    }
+
+    result
+}
+
+/// Formats `value` as a literal qalc will read back with the active decimal separator.
+pub fn number_literal(value: f64, decimal_comma: bool) -> String {
+    let literal = value.to_string();
+    if decimal_comma {
+        literal.replace('.', ",")
+    } else {
+        literal
+    }
+}
+
+/// Adds two decimal values, rounding the sum to the decimals they are
+/// written with. This drops the binary float error of sums such as
+/// 0.1 + 0.2 while keeping every digit an `f64` holds, so running totals
+/// don't drift.
+pub fn add_decimals(a: f64, b: f64) -> f64 {
+    // The shortest representation reads back as the same value.
+    let decimals = |value: f64| {
+        let text = value.to_string();
+        text.split_once('.')
+            .map_or(0, |(_, decimals)| decimals.len())
+    };
+    let sum = a + b;
+    format!("{sum:.*}", decimals(a).max(decimals(b)))
+        .parse()
+        .unwrap_or(sum)
+}
+
+/// Formats a money amount with two decimals and the active decimal separator.
+pub fn format_amount(value: f64, decimal_comma: bool) -> String {
+    let value = if value.abs() < 0.005 { 0.0 } else { value };
+    let text = format!("{value:.2}");
+    if decimal_comma {
+        text.replace('.', ",")
+    } else {
+        text
+    }
+}
+
+/// Parses a numeric qalc result such as `-1.5`, `≈ 0.333333333` or `1.2E-7`,
+/// or a result as displayed, such as `1.2×10⁻⁷`.
+pub fn parse_number(text: &str, decimal_comma: bool) -> Option<f64> {
+    let mut text = display::plain(text, decimal_comma)
+        .trim()
+        .trim_start_matches('≈')
+        .trim()
+        .replace('−', "-")
+        .replace(char::is_whitespace, "");
+    if decimal_comma {
+        text = text.replace('.', "").replace(',', ".");
+    }
+
+    for separator in ["×10^", "*10^"] {
+        if let Some((mantissa, exponent)) = text.split_once(separator) {
+            let mantissa: f64 = mantissa.parse().ok()?;
+            let exponent: i32 = exponent.trim_matches(['(', ')']).parse().ok()?;
+            return Some(mantissa * 10f64.powi(exponent));
+        }
+    }
+
+    text.parse().ok().filter(|value: &f64| value.is_finite())
+}
+
+/// Splits a result such as `50.43±0.99` into its value and uncertainty.
+pub fn parse_uncertainty(text: &str, decimal_comma: bool) -> Option<(f64, f64)> {
+    let (value, uncertainty) = text.split_once('±')?;
+    Some((
+        parse_number(value, decimal_comma)?,
+        parse_number(uncertainty, decimal_comma)?.abs(),
+    ))
+}
+
 /// Returns the version of the `qalc` command-line tool.
 fn qalc_version() -> Option<String> {
     let output = Command::new("qalc").arg("--version").output().ok()?;
@@ -149,9 +688,151 @@ pub async fn uses_decimal_comma() -> bool {
     false
 }
 
+/// Finds how the system groups digits, so results can follow it. Groups of
+/// three are the fallback when the locale has no separator, as in `C`.
+pub async fn locale_grouping() -> DigitGrouping {
+    let spawn_result = Command::new("locale")
+        .arg("-ck")
+        .arg("thousands_sep")
+        .arg("grouping")
+        .stderr(Stdio::null())
+        .output();
+
+    if let Ok(output) = spawn_result
+        && let Ok(string) = String::from_utf8(output.stdout)
+    {
+        let value = |key: &str| {
+            string
+                .lines()
+                .find_map(|line| Some(line.strip_prefix(key)?.strip_prefix('=')?.trim_matches('"')))
+        };
+        // Indian English groups as 3;2 for lakhs and crores.
+        if value("grouping").is_some_and(|grouping| grouping.starts_with("3;2")) {
+            return DigitGrouping::Indian;
+        }
+        // Locales grouping with a space or an apostrophe get thin spaces.
+        if value("thousands_sep").is_some_and(|separator| !matches!(separator, "" | "," | ".")) {
+            return DigitGrouping::ThinSpace;
+        }
+    }
+
+    DigitGrouping::Thousands
+}
+
 pub fn autocalc() -> bool {
     let min_version = Version::parse("5.4.0").unwrap();
     qalc_version()
         .and_then(|version| Version::parse(&version).ok())
         .is_some_and(|current| current >= min_version)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn desk_percent_adds_and_removes_a_share_of_the_base() {
+        assert_eq!(desk_percent("200+10%"), "(200)*(1+10/100)");
+        assert_eq!(desk_percent("200-10%"), "(200)*(1-10/100)");
+        assert_eq!(desk_percent("200−10%"), "(200)*(1-10/100)");
+        assert_eq!(desk_percent("(50+50)+10%"), "((50+50))*(1+10/100)");
+    }
+
+    #[test]
+    fn desk_percent_multiplies_and_divides_by_the_percentage() {
+        assert_eq!(desk_percent("200*10%"), "200*(10/100)");
+        assert_eq!(desk_percent("200×10%"), "200×(10/100)");
+        assert_eq!(desk_percent("200/10%"), "200/(10/100)");
+        assert_eq!(desk_percent("200÷10%"), "200÷(10/100)");
+    }
+
+    #[test]
+    fn desk_percent_uses_the_enclosing_parentheses_as_base() {
+        assert_eq!(desk_percent("2*(100+10%)"), "2*((100)*(1+10/100))");
+        assert_eq!(desk_percent("2+(3*(100-50%))"), "2+(3*((100)*(1-50/100)))");
+        assert_eq!(desk_percent("5+(10)%"), "(5)*(1+(10)/100)");
+    }
+
+    #[test]
+    fn desk_percent_leaves_bare_and_unary_percentages() {
+        assert_eq!(desk_percent("10%"), "10%");
+        assert_eq!(desk_percent("-10%"), "-10%");
+        assert_eq!(desk_percent("2*-10%"), "2*-10%");
+    }
+
+    #[test]
+    fn desk_percent_passes_mod_through() {
+        assert_eq!(desk_percent("10 mod 3"), "10 mod 3");
+        assert_eq!(desk_percent("200 mod 10%"), "200 mod 10%");
+    }
+
+    #[test]
+    fn toggle_sign_removes_a_unary_minus_after_operator_words() {
+        let mut calculator = Calculator {
+            expression: String::from("5 mod 3"),
+            ..Calculator::default()
+        };
+        calculator.toggle_sign();
+        assert_eq!(calculator.expression, "5 mod -3");
+        calculator.toggle_sign();
+        assert_eq!(calculator.expression, "5 mod 3");
+
+        calculator.expression = String::from("5 + -3");
+        calculator.toggle_sign();
+        assert_eq!(calculator.expression, "5 + 3");
+
+        calculator.expression = String::from("5-3");
+        calculator.toggle_sign();
+        assert_eq!(calculator.expression, "5--3");
+    }
+
+    #[test]
+    fn literal_percent_mode_leaves_the_expression_alone() {
+        assert_eq!(
+            apply_percent_mode("200+10%", PercentMode::Literal),
+            "200+10%"
+        );
+        assert_eq!(
+            apply_percent_mode("200+10%", PercentMode::Desk),
+            "(200)*(1+10/100)"
+        );
+    }
+
+    #[test]
+    fn add_decimals_keeps_cents_of_large_totals() {
+        assert_eq!(add_decimals(0.1, 0.2), 0.3);
+        assert_eq!(add_decimals(12_345_678_901.23, 0.01), 12_345_678_901.24);
+        assert_eq!(
+            add_decimals(1_234_567_890_123.45, 1.1),
+            1_234_567_890_124.55
+        );
+        assert_eq!(add_decimals(19.99, -19.99), 0.0);
+        assert_eq!(add_decimals(1e-7, 2e-7), 3e-7);
+    }
+
+    #[test]
+    fn split_batch_at_the_echoed_sentinel() {
+        let output = "cosmic_calculator_batch_end\n\
+                      3\n\
+                      cosmic_calculator_batch_end\n\
+                      \n\
+                      cosmic_calculator_batch_end\n\
+                      918273645\n\
+                      cosmic_calculator_batch_end\n";
+        assert_eq!(
+            split_batch(output, 3, false),
+            Some(vec![
+                String::from("3"),
+                String::new(),
+                String::from("918273645")
+            ])
+        );
+        assert_eq!(split_batch(output, 2, false), None);
+    }
+
+    #[test]
+    fn split_batch_rejects_a_numeric_sentinel() {
+        assert_eq!(split_batch("7\n1\n7\n", 1, false), None);
+        assert_eq!(split_batch("", 0, false), None);
+    }
+}
@@ -5,6 +5,7 @@ pub enum Operator {
     Multiply,
     Divide,
     Modulus,
+    Percent,
     Point,
     Equal,
     Clear,
@@ -14,16 +15,60 @@ pub enum Operator {
     ParenthesesClose,
     Power,
     SquareRoot,
+    TaxPlus,
+    TaxMinus,
+    GrandTotal,
+    ClearGrandTotal,
+    MemoryAdd,
+    MemorySubtract,
+    MemoryRecall,
+    MemoryClear,
+    Enter,
+    Swap,
+    Roll,
+    Drop,
+    LastX,
 }
 
 impl Operator {
+    /// Operators that combine two operands.
+    pub const BINARY: [Self; 6] = [
+        Self::Add,
+        Self::Subtract,
+        Self::Multiply,
+        Self::Divide,
+        Self::Modulus,
+        Self::Power,
+    ];
+
+    /// Keys that act on the stack in RPN mode.
+    pub const STACK: [Self; 16] = [
+        Self::Add,
+        Self::Subtract,
+        Self::Multiply,
+        Self::Divide,
+        Self::Modulus,
+        Self::Power,
+        Self::Percent,
+        Self::SquareRoot,
+        Self::Negate,
+        Self::Clear,
+        Self::Equal,
+        Self::Enter,
+        Self::Swap,
+        Self::Roll,
+        Self::Drop,
+        Self::LastX,
+    ];
+
     pub fn display(&self) -> &str {
         match self {
             Self::Add => "+",
             Self::Subtract => "-",
             Self::Multiply => "x",
             Self::Divide => "÷",
-            Self::Modulus => "%",
+            Self::Modulus => "mod",
+            Self::Percent => "%",
             Self::Point => ".",
             Self::Equal => "=",
             Self::ParenthesesOpen => "(",
@@ -33,6 +78,19 @@ impl Operator {
             Self::Clear => "C",
             Self::Backspace => "⌫",
             Self::Negate => "±",
+            Self::TaxPlus => "TAX+",
+            Self::TaxMinus => "TAX−",
+            Self::GrandTotal => "GT",
+            Self::ClearGrandTotal => "CGT",
+            Self::MemoryAdd => "M+",
+            Self::MemorySubtract => "M−",
+            Self::MemoryRecall => "MR",
+            Self::MemoryClear => "MC",
+            Self::Enter => "ENTER",
+            Self::Swap => "x↔y",
+            Self::Roll => "R↓",
+            Self::Drop => "DROP",
+            Self::LastX => "LASTx",
         }
     }
 
@@ -42,7 +100,8 @@ impl Operator {
             Self::Subtract => "-",
             Self::Multiply => "*",
             Self::Divide => "/",
-            Self::Modulus => "%",
+            Self::Modulus => " mod ",
+            Self::Percent => "%",
             Self::Point => ".",
             Self::Equal => "=",
             Self::ParenthesesOpen => "(",
@@ -52,6 +111,19 @@ impl Operator {
             Self::Clear => "C",
             Self::Backspace => "⌫",
             Self::Negate => "±",
+            Self::TaxPlus
+            | Self::TaxMinus
+            | Self::GrandTotal
+            | Self::ClearGrandTotal
+            | Self::MemoryAdd
+            | Self::MemorySubtract
+            | Self::MemoryRecall
+            | Self::MemoryClear
+            | Self::Enter
+            | Self::Swap
+            | Self::Roll
+            | Self::Drop
+            | Self::LastX => "",
         }
     }
-}
\ No newline at end of file
+}
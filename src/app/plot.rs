@@ -0,0 +1,715 @@
+use cosmic::{
+    Element,
+    iced::{Length, Point, Task, mouse::ScrollDelta, widget::mouse_area},
+    widget::{self, image},
+};
+
+use crate::app::{operations, tool::Action};
+use crate::fl;
+
+/// Size of the rendered plot in pixels.
+const WIDTH: u32 = 400;
+const HEIGHT: u32 = 280;
+/// Points sampled per function across the visible x range.
+const SAMPLES: usize = 240;
+
+const COLORS: [[u8; 3]; 5] = [
+    [53, 132, 228],
+    [230, 97, 0],
+    [46, 194, 126],
+    [224, 27, 36],
+    [145, 65, 172],
+];
+const GRID: [u8; 3] = [128, 128, 128];
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Function(usize, String),
+    AddFunction,
+    RemoveFunction(usize),
+    XMin(String),
+    XMax(String),
+    YMin(String),
+    YMax(String),
+    ApplyRange,
+    Plot,
+    Sampled(u64, Vec<f64>, Vec<Vec<Option<f64>>>),
+    ZoomIn,
+    ZoomOut,
+    Reset,
+    Hover(Point),
+    Exit,
+    Press,
+    Release,
+    Scroll(ScrollDelta),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Viewport {
+    x_min: f64,
+    x_max: f64,
+    y_min: f64,
+    y_max: f64,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self {
+            x_min: -10.0,
+            x_max: 10.0,
+            y_min: -7.0,
+            y_max: 7.0,
+        }
+    }
+}
+
+impl Viewport {
+    fn to_screen(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            (x - self.x_min) / (self.x_max - self.x_min) * f64::from(WIDTH - 1),
+            (self.y_max - y) / (self.y_max - self.y_min) * f64::from(HEIGHT - 1),
+        )
+    }
+
+    fn to_world(&self, point: Point) -> (f64, f64) {
+        (
+            self.x_min + f64::from(point.x) / f64::from(WIDTH - 1) * (self.x_max - self.x_min),
+            self.y_max - f64::from(point.y) / f64::from(HEIGHT - 1) * (self.y_max - self.y_min),
+        )
+    }
+
+    /// Scales the viewport by `factor` around the world point `(x, y)`.
+    fn zoom(&mut self, factor: f64, (x, y): (f64, f64)) {
+        self.x_min = x + (self.x_min - x) * factor;
+        self.x_max = x + (self.x_max - x) * factor;
+        self.y_min = y + (self.y_min - y) * factor;
+        self.y_max = y + (self.y_max - y) * factor;
+    }
+
+    /// Moves the viewport by a drag of `(dx, dy)` pixels.
+    fn pan(&mut self, dx: f32, dy: f32) {
+        let dx = f64::from(dx) / f64::from(WIDTH - 1) * (self.x_max - self.x_min);
+        let dy = f64::from(dy) / f64::from(HEIGHT - 1) * (self.y_max - self.y_min);
+        self.x_min -= dx;
+        self.x_max -= dx;
+        self.y_min += dy;
+        self.y_max += dy;
+    }
+
+    fn center(&self) -> (f64, f64) {
+        (
+            (self.x_min + self.x_max) / 2.0,
+            (self.y_min + self.y_max) / 2.0,
+        )
+    }
+}
+
+pub struct Plot {
+    functions: Vec<String>,
+    viewport: Viewport,
+    x_min: String,
+    x_max: String,
+    y_min: String,
+    y_max: String,
+    xs: Vec<f64>,
+    samples: Vec<Vec<Option<f64>>>,
+    generation: u64,
+    hover: Option<Point>,
+    dragging: bool,
+    image: image::Handle,
+}
+
+impl Default for Plot {
+    fn default() -> Self {
+        let mut plot = Self {
+            functions: vec![String::new()],
+            viewport: Viewport::default(),
+            x_min: String::new(),
+            x_max: String::new(),
+            y_min: String::new(),
+            y_max: String::new(),
+            xs: vec![],
+            samples: vec![],
+            generation: 0,
+            hover: None,
+            dragging: false,
+            image: image::Handle::from_rgba(1, 1, vec![0; 4]),
+        };
+        plot.sync_range(false);
+        plot.render(false);
+        plot
+    }
+}
+
+impl Plot {
+    pub fn update(&mut self, message: Message, decimal_comma: bool) -> Action<Message> {
+        match message {
+            Message::Function(index, function) => {
+                if let Some(slot) = self.functions.get_mut(index) {
+                    *slot = function;
+                }
+            }
+            Message::AddFunction => {
+                if self.functions.len() < COLORS.len() {
+                    self.functions.push(String::new());
+                }
+            }
+            Message::RemoveFunction(index) => {
+                if self.functions.len() > 1 {
+                    self.functions.remove(index);
+                    return self.sample(decimal_comma).into();
+                }
+            }
+            Message::XMin(value) => self.x_min = value,
+            Message::XMax(value) => self.x_max = value,
+            Message::YMin(value) => self.y_min = value,
+            Message::YMax(value) => self.y_max = value,
+            Message::ApplyRange => {
+                let parse = |text: &str| operations::parse_number(text, decimal_comma);
+                if let (Some(x_min), Some(x_max), Some(y_min), Some(y_max)) = (
+                    parse(&self.x_min),
+                    parse(&self.x_max),
+                    parse(&self.y_min),
+                    parse(&self.y_max),
+                ) && x_min < x_max
+                    && y_min < y_max
+                {
+                    self.viewport = Viewport {
+                        x_min,
+                        x_max,
+                        y_min,
+                        y_max,
+                    };
+                    return self.sample(decimal_comma).into();
+                }
+                self.sync_range(decimal_comma);
+            }
+            Message::Plot => return self.sample(decimal_comma).into(),
+            Message::Sampled(generation, xs, samples) => {
+                // A newer request superseded this one while qalc was running.
+                if generation == self.generation {
+                    self.xs = xs;
+                    self.samples = samples;
+                    self.render(decimal_comma);
+                }
+            }
+            Message::ZoomIn => {
+                self.viewport.zoom(0.5, self.viewport.center());
+                return self.sample(decimal_comma).into();
+            }
+            Message::ZoomOut => {
+                self.viewport.zoom(2.0, self.viewport.center());
+                return self.sample(decimal_comma).into();
+            }
+            Message::Reset => {
+                self.viewport = Viewport::default();
+                return self.sample(decimal_comma).into();
+            }
+            Message::Hover(point) => {
+                if self.dragging
+                    && let Some(hover) = self.hover
+                {
+                    self.viewport.pan(point.x - hover.x, point.y - hover.y);
+                    self.sync_range(decimal_comma);
+                }
+                self.hover = Some(point);
+                self.render(decimal_comma);
+            }
+            Message::Exit => {
+                self.hover = None;
+                if std::mem::take(&mut self.dragging) {
+                    return self.sample(decimal_comma).into();
+                }
+                self.render(decimal_comma);
+            }
+            Message::Press => self.dragging = self.hover.is_some(),
+            Message::Release => {
+                if std::mem::take(&mut self.dragging) {
+                    return self.sample(decimal_comma).into();
+                }
+            }
+            Message::Scroll(delta) => {
+                let y = match delta {
+                    ScrollDelta::Lines { y, .. } => y,
+                    ScrollDelta::Pixels { y, .. } => y / 20.0,
+                };
+                let around = self.hover.map_or(self.viewport.center(), |point| {
+                    self.viewport.to_world(point)
+                });
+                self.viewport.zoom(if y > 0.0 { 0.8 } else { 1.25 }, around);
+                return self.sample(decimal_comma).into();
+            }
+        }
+        Action::None
+    }
+
+    /// Samples every function over the visible x range through qalc.
+    fn sample(&mut self, decimal_comma: bool) -> Task<Message> {
+        self.sync_range(decimal_comma);
+        self.render(decimal_comma);
+        self.generation += 1;
+
+        let generation = self.generation;
+        let functions = self.functions.clone();
+        let Viewport { x_min, x_max, .. } = self.viewport;
+
+        Task::perform(
+            async move {
+                let xs = (0..SAMPLES)
+                    .map(|i| x_min + (x_max - x_min) * i as f64 / (SAMPLES - 1) as f64)
+                    .collect::<Vec<_>>();
+                let samples = functions
+                    .iter()
+                    .map(|function| sample_function(function, &xs, decimal_comma))
+                    .collect();
+                (xs, samples)
+            },
+            move |(xs, samples)| Message::Sampled(generation, xs, samples),
+        )
+    }
+
+    /// Writes the viewport into the range fields so `ApplyRange` reads it back unchanged.
+    fn sync_range(&mut self, decimal_comma: bool) {
+        let literal = |value: f64| operations::number_literal(tidy(value), decimal_comma);
+        self.x_min = literal(self.viewport.x_min);
+        self.x_max = literal(self.viewport.x_max);
+        self.y_min = literal(self.viewport.y_min);
+        self.y_max = literal(self.viewport.y_max);
+    }
+
+    /// Index of the sample closest to the hovered pixel.
+    fn hovered_sample(&self) -> Option<usize> {
+        let (x, _) = self.viewport.to_world(self.hover?);
+        let (first, last) = (*self.xs.first()?, *self.xs.last()?);
+        if x < first || x > last || last <= first {
+            return None;
+        }
+        let index = ((x - first) / (last - first) * (self.xs.len() - 1) as f64).round();
+        Some(index as usize)
+    }
+
+    fn render(&mut self, decimal_comma: bool) {
+        let mut canvas = Canvas::new(WIDTH, HEIGHT);
+        let viewport = self.viewport;
+
+        // Grid lines and tick labels.
+        let x_step = tick_step(viewport.x_max - viewport.x_min);
+        let y_step = tick_step(viewport.y_max - viewport.y_min);
+        let (axis_x, axis_y) = viewport.to_screen(0.0, 0.0);
+        let label_y = axis_y.clamp(2.0, f64::from(HEIGHT) - 8.0) as i32 + 2;
+        let label_x = axis_x.clamp(2.0, f64::from(WIDTH) - 30.0) as i32 + 3;
+
+        for x in ticks(viewport.x_min, viewport.x_max, x_step) {
+            let (px, _) = viewport.to_screen(x, 0.0);
+            canvas.vertical(px.round() as i32, GRID, 0.25);
+            if x.abs() > x_step / 2.0 {
+                canvas.text(
+                    px.round() as i32 + 2,
+                    label_y,
+                    &format_tick(x, decimal_comma),
+                    GRID,
+                );
+            }
+        }
+        for y in ticks(viewport.y_min, viewport.y_max, y_step) {
+            let (_, py) = viewport.to_screen(0.0, y);
+            canvas.horizontal(py.round() as i32, GRID, 0.25);
+            if y.abs() > y_step / 2.0 {
+                canvas.text(
+                    label_x,
+                    py.round() as i32 + 2,
+                    &format_tick(y, decimal_comma),
+                    GRID,
+                );
+            }
+        }
+
+        // Axes.
+        canvas.vertical(axis_x.round() as i32, GRID, 0.9);
+        canvas.horizontal(axis_y.round() as i32, GRID, 0.9);
+        canvas.text(WIDTH as i32 - 8, label_y, "x", GRID);
+        canvas.text(label_x, 2, "y", GRID);
+
+        // Curves.
+        for (samples, color) in self.samples.iter().zip(COLORS) {
+            for (a, b) in self
+                .xs
+                .iter()
+                .zip(samples)
+                .zip(self.xs.iter().zip(samples).skip(1))
+            {
+                let ((&x0, &Some(y0)), (&x1, &Some(y1))) = (a, b) else {
+                    continue;
+                };
+                let (x0, y0) = viewport.to_screen(x0, y0);
+                let (x1, y1) = viewport.to_screen(x1, y1);
+                // Skip vertical asymptotes instead of joining both branches.
+                if (y1 - y0).abs() > f64::from(HEIGHT) * 4.0 {
+                    continue;
+                }
+                canvas.line(x0, y0, x1, y1, color);
+            }
+        }
+
+        // Hover marker.
+        if let Some(index) = self.hovered_sample() {
+            let (px, _) = viewport.to_screen(self.xs[index], 0.0);
+            canvas.vertical(px.round() as i32, GRID, 0.6);
+            for (samples, color) in self.samples.iter().zip(COLORS) {
+                if let Some(Some(y)) = samples.get(index) {
+                    let (px, py) = viewport.to_screen(self.xs[index], *y);
+                    canvas.dot(px.round() as i32, py.round() as i32, color);
+                }
+            }
+        }
+
+        self.image = image::Handle::from_rgba(WIDTH, HEIGHT, canvas.pixels);
+    }
+
+    pub fn view(&self, decimal_comma: bool) -> Element<'_, Message> {
+        let spacing = cosmic::theme::active().cosmic().spacing;
+
+        let mut functions = widget::settings::section().title(fl!("functions"));
+        for (index, function) in self.functions.iter().enumerate() {
+            let mut row = widget::row::with_capacity(2)
+                .push(
+                    widget::text_input(format!("f{}(x)", index + 1), function)
+                        .on_input(move |function| Message::Function(index, function))
+                        .on_submit(|_| Message::Plot)
+                        .width(Length::Fill),
+                )
+                .spacing(spacing.space_xxs)
+                .align_y(cosmic::iced::Alignment::Center);
+            if self.functions.len() > 1 {
+                row = row.push(
+                    widget::button::icon(widget::icon::from_name("list-remove-symbolic"))
+                        .on_press(Message::RemoveFunction(index)),
+                );
+            }
+            functions = functions.add(row);
+        }
+
+        let range = widget::settings::section()
+            .title(fl!("range"))
+            .add(widget::settings::item(
+                "x",
+                widget::row::with_capacity(2)
+                    .push(
+                        widget::text_input(fl!("minimum"), &self.x_min)
+                            .on_input(Message::XMin)
+                            .on_submit(|_| Message::ApplyRange),
+                    )
+                    .push(
+                        widget::text_input(fl!("maximum"), &self.x_max)
+                            .on_input(Message::XMax)
+                            .on_submit(|_| Message::ApplyRange),
+                    )
+                    .spacing(spacing.space_xxs),
+            ))
+            .add(widget::settings::item(
+                "y",
+                widget::row::with_capacity(2)
+                    .push(
+                        widget::text_input(fl!("minimum"), &self.y_min)
+                            .on_input(Message::YMin)
+                            .on_submit(|_| Message::ApplyRange),
+                    )
+                    .push(
+                        widget::text_input(fl!("maximum"), &self.y_max)
+                            .on_input(Message::YMax)
+                            .on_submit(|_| Message::ApplyRange),
+                    )
+                    .spacing(spacing.space_xxs),
+            ));
+
+        let controls = widget::row::with_capacity(5)
+            .push(widget::button::suggested(fl!("plot")).on_press(Message::Plot))
+            .push(
+                widget::button::standard(fl!("add-function")).on_press_maybe(
+                    (self.functions.len() < COLORS.len()).then_some(Message::AddFunction),
+                ),
+            )
+            .push(widget::button::standard("+").on_press(Message::ZoomIn))
+            .push(widget::button::standard("−").on_press(Message::ZoomOut))
+            .push(widget::button::standard(fl!("reset")).on_press(Message::Reset))
+            .spacing(spacing.space_xxs);
+
+        let graph = mouse_area(
+            widget::image(self.image.clone())
+                .width(Length::Fixed(WIDTH as f32))
+                .height(Length::Fixed(HEIGHT as f32)),
+        )
+        .on_move(Message::Hover)
+        .on_exit(Message::Exit)
+        .on_press(Message::Press)
+        .on_release(Message::Release)
+        .on_scroll(Message::Scroll);
+
+        let readout = match self.hovered_sample() {
+            Some(index) => {
+                let mut readout = format!("x = {}", format_value(self.xs[index], decimal_comma));
+                for (number, samples) in self.samples.iter().enumerate() {
+                    let value = samples
+                        .get(index)
+                        .copied()
+                        .flatten()
+                        .map_or(String::from("—"), |value| {
+                            format_value(value, decimal_comma)
+                        });
+                    readout.push_str(&format!(", f{}(x) = {value}", number + 1));
+                }
+                readout
+            }
+            None => String::new(),
+        };
+
+        widget::settings::view_column(vec![
+            functions.into(),
+            controls.into(),
+            graph.into(),
+            widget::text::caption(readout).into(),
+            range.into(),
+        ])
+        .into()
+    }
+}
+
+/// Evaluates `function` at every x, keeping only finite numeric results.
+fn sample_function(function: &str, xs: &[f64], decimal_comma: bool) -> Vec<Option<f64>> {
+    if function.trim().is_empty() {
+        return vec![None; xs.len()];
+    }
+    let expressions = xs
+        .iter()
+        .map(|x| {
+            operations::substitute(
+                function,
+                "x",
+                &operations::number_literal(*x, decimal_comma),
+            )
+        })
+        .collect::<Vec<_>>();
+    operations::evaluate_batch(&expressions, decimal_comma, &["approximation approximate"])
+        .into_iter()
+        .map(|result| {
+            result
+                .ok()
+                .and_then(|value| operations::parse_number(&value, decimal_comma))
+        })
+        .collect()
+}
+
+/// Picks a 1, 2 or 5 × 10ⁿ grid step giving roughly eight lines over `span`.
+fn tick_step(span: f64) -> f64 {
+    let raw = span / 8.0;
+    let magnitude = 10f64.powf(raw.log10().floor());
+    match raw / magnitude {
+        r if r < 1.5 => magnitude,
+        r if r < 3.5 => 2.0 * magnitude,
+        r if r < 7.5 => 5.0 * magnitude,
+        _ => 10.0 * magnitude,
+    }
+}
+
+/// Multiples of `step` within `[min, max]`, or none if there would be too many.
+fn ticks(min: f64, max: f64, step: f64) -> impl Iterator<Item = f64> {
+    let first = (min / step).ceil();
+    let count = (max / step).floor() - first;
+    let count = if (0.0..=100.0).contains(&count) {
+        count as i32 + 1
+    } else {
+        0
+    };
+    (0..count).map(move |i| (first + f64::from(i)) * step)
+}
+
+/// Rounds away float noise such as `-10.000000000000002` left by zooming and panning.
+fn tidy(value: f64) -> f64 {
+    format!("{value:.9e}").parse().unwrap_or(value)
+}
+
+fn format_tick(value: f64, decimal_comma: bool) -> String {
+    let value = if value.abs() < 1e-12 { 0.0 } else { value };
+    let text = if value != 0.0 && (value.abs() >= 1e5 || value.abs() < 1e-3) {
+        format!("{value:.1e}")
+    } else {
+        let text = format!("{value:.4}");
+        text.trim_end_matches('0').trim_end_matches('.').to_string()
+    };
+    with_separator(text, decimal_comma)
+}
+
+fn format_value(value: f64, decimal_comma: bool) -> String {
+    let text = format!("{value:.6}");
+    let text = text.trim_end_matches('0').trim_end_matches('.').to_string();
+    with_separator(text, decimal_comma)
+}
+
+fn with_separator(text: String, decimal_comma: bool) -> String {
+    if decimal_comma {
+        text.replace('.', ",")
+    } else {
+        text
+    }
+}
+
+/// A tiny RGBA software canvas; everything is drawn on the CPU.
+struct Canvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![0; (width * height * 4) as usize],
+        }
+    }
+
+    /// Blends `color` over the pixel at `(x, y)` with the given opacity.
+    fn blend(&mut self, x: i32, y: i32, [r, g, b]: [u8; 3], alpha: f32) {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return;
+        }
+        let index = ((y as u32 * self.width + x as u32) * 4) as usize;
+        let pixel = &mut self.pixels[index..index + 4];
+        let dst_alpha = f32::from(pixel[3]) / 255.0;
+        let out_alpha = alpha + dst_alpha * (1.0 - alpha);
+        if out_alpha <= 0.0 {
+            return;
+        }
+        for (channel, source) in pixel.iter_mut().zip([r, g, b]) {
+            let mixed = (f32::from(source) * alpha
+                + f32::from(*channel) * dst_alpha * (1.0 - alpha))
+                / out_alpha;
+            *channel = mixed.round() as u8;
+        }
+        pixel[3] = (out_alpha * 255.0).round() as u8;
+    }
+
+    fn vertical(&mut self, x: i32, color: [u8; 3], alpha: f32) {
+        for y in 0..self.height as i32 {
+            self.blend(x, y, color, alpha);
+        }
+    }
+
+    fn horizontal(&mut self, y: i32, color: [u8; 3], alpha: f32) {
+        for x in 0..self.width as i32 {
+            self.blend(x, y, color, alpha);
+        }
+    }
+
+    /// Draws a two pixel wide line from `(x0, y0)` to `(x1, y1)`.
+    fn line(&mut self, x0: f64, y0: f64, x1: f64, y1: f64, color: [u8; 3]) {
+        let steps = (x1 - x0).abs().max((y1 - y0).abs()).ceil().max(1.0);
+        // Avoid walking millions of off-canvas pixels for near-vertical jumps.
+        if steps > f64::from(self.width + self.height) * 4.0 {
+            return;
+        }
+        for step in 0..=steps as i32 {
+            let t = f64::from(step) / steps;
+            let x = (x0 + (x1 - x0) * t).round() as i32;
+            let y = (y0 + (y1 - y0) * t).round() as i32;
+            self.blend(x, y, color, 1.0);
+            self.blend(x + 1, y, color, 0.5);
+            self.blend(x, y + 1, color, 0.5);
+        }
+    }
+
+    fn dot(&mut self, x: i32, y: i32, color: [u8; 3]) {
+        for dy in -2i32..=2 {
+            for dx in -2i32..=2 {
+                if dx.abs() + dy.abs() <= 3 {
+                    self.blend(x + dx, y + dy, color, 1.0);
+                }
+            }
+        }
+    }
+
+    /// Draws `text` with a 3×5 bitmap font; unsupported characters are skipped.
+    fn text(&mut self, x: i32, y: i32, text: &str, color: [u8; 3]) {
+        for (offset, c) in text.chars().enumerate() {
+            let Some(glyph) = glyph(c) else {
+                continue;
+            };
+            for row in 0..5 {
+                for column in 0..3 {
+                    if glyph & (1 << (14 - (row * 3 + column))) != 0 {
+                        self.blend(x + offset as i32 * 4 + column, y + row, color, 1.0);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 3×5 glyph bitmaps, row by row from the top, most significant bit first.
+fn glyph(c: char) -> Option<u16> {
+    Some(match c {
+        '0' => 0b111_101_101_101_111,
+        '1' => 0b010_110_010_010_111,
+        '2' => 0b111_001_111_100_111,
+        '3' => 0b111_001_111_001_111,
+        '4' => 0b101_101_111_001_001,
+        '5' => 0b111_100_111_001_111,
+        '6' => 0b111_100_111_101_111,
+        '7' => 0b111_001_010_010_010,
+        '8' => 0b111_101_111_101_111,
+        '9' => 0b111_101_111_001_111,
+        '-' => 0b000_000_111_000_000,
+        '+' => 0b000_010_111_010_000,
+        '.' => 0b000_000_000_000_010,
+        ',' => 0b000_000_000_010_100,
+        'e' => 0b000_111_111_100_111,
+        'x' => 0b000_101_010_101_000,
+        'y' => 0b101_101_010_010_010,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range_text_reads_back_in_both_locales() {
+        for decimal_comma in [false, true] {
+            for value in [
+                -3.5,
+                1.0e5,
+                -10.000_000_000_000_002,
+                0.000_123_4,
+                123_456.789,
+            ] {
+                let text = operations::number_literal(tidy(value), decimal_comma);
+                let parsed = operations::parse_number(&text, decimal_comma).unwrap();
+                assert!((parsed - value).abs() <= value.abs() * 1e-9, "{text}");
+            }
+        }
+    }
+
+    #[test]
+    fn range_text_uses_the_decimal_separator() {
+        let mut plot = Plot::default();
+        plot.viewport.x_min = -3.5;
+        plot.sync_range(true);
+        assert_eq!(plot.x_min, "-3,5");
+        plot.sync_range(false);
+        assert_eq!(plot.x_min, "-3.5");
+    }
+
+    #[test]
+    fn labels_read_back_in_both_locales() {
+        for decimal_comma in [false, true] {
+            let parse = |text: String| operations::parse_number(&text, decimal_comma).unwrap();
+            assert_eq!(parse(format_tick(-3.5, decimal_comma)), -3.5);
+            assert_eq!(parse(format_tick(150_000.0, decimal_comma)), 150_000.0);
+            assert_eq!(parse(format_value(0.125, decimal_comma)), 0.125);
+        }
+        assert_eq!(format_tick(1.0e5, true), "1,0e5");
+        assert_eq!(format_value(-2.5, true), "-2,5");
+    }
+}
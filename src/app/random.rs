@@ -0,0 +1,421 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use cosmic::{Element, widget};
+
+use crate::app::{
+    operations::{self, Calculator, EvaluateError},
+    tool::Action,
+};
+use crate::fl;
+
+/// Upper bound on numbers drawn or dice rolled at once.
+const MAX_DRAWS: u64 = 1000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Mode {
+    #[default]
+    Integer,
+    Float,
+    Dice,
+    Pick,
+    Shuffle,
+}
+
+impl Mode {
+    const ALL: [Self; 5] = [
+        Self::Integer,
+        Self::Float,
+        Self::Dice,
+        Self::Pick,
+        Self::Shuffle,
+    ];
+
+    fn label(&self) -> String {
+        match self {
+            Self::Integer => fl!("random-integer"),
+            Self::Float => fl!("random-float"),
+            Self::Dice => fl!("dice"),
+            Self::Pick => fl!("pick"),
+            Self::Shuffle => fl!("shuffle"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Mode(usize),
+    Minimum(String),
+    Maximum(String),
+    Count(String),
+    Dice(String),
+    Items(String),
+    Seed(String),
+    Generate,
+}
+
+/// xoshiro256** seeded through SplitMix64, so a seed gives the same draws
+/// on every machine.
+pub struct Rng {
+    state: [u64; 4],
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        let mut seed = seed;
+        let mut next = || {
+            seed = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = seed;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^ (z >> 31)
+        };
+        Self {
+            state: [next(), next(), next(), next()],
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let [s0, s1, s2, s3] = &mut self.state;
+        let result = s1.wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = *s1 << 17;
+        *s2 ^= *s0;
+        *s3 ^= *s1;
+        *s1 ^= *s2;
+        *s0 ^= *s3;
+        *s2 ^= t;
+        *s3 = s3.rotate_left(45);
+        result
+    }
+
+    /// A uniform integer in `0..bound`, without modulo bias.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        let threshold = bound.wrapping_neg() % bound;
+        loop {
+            let value = self.next_u64();
+            if value >= threshold {
+                return value % bound;
+            }
+        }
+    }
+
+    /// A uniform integer in `min..=max`.
+    pub fn range(&mut self, min: i64, max: i64) -> i64 {
+        let span = max.abs_diff(min);
+        let offset = if span == u64::MAX {
+            self.next_u64()
+        } else {
+            self.below(span + 1)
+        };
+        min.wrapping_add_unsigned(offset)
+    }
+
+    /// A uniform float in `0..1`.
+    pub fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for index in (1..items.len()).rev() {
+            let other = self.below(index as u64 + 1) as usize;
+            items.swap(index, other);
+        }
+    }
+}
+
+/// One `NdM` group or constant in dice notation such as `3d6+2`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Term {
+    Dice { count: u64, sides: u64, sign: i64 },
+    Constant(i64),
+}
+
+/// Parses dice notation: `d20`, `3d6+2`, `2d8+1d4-1`.
+fn parse_dice(notation: &str) -> Option<Vec<Term>> {
+    let notation = notation.replace(char::is_whitespace, "").to_lowercase();
+    let mut parts = vec![(1, String::new())];
+    for c in notation.chars() {
+        match c {
+            '+' => parts.push((1, String::new())),
+            '-' => parts.push((-1, String::new())),
+            _ => parts.last_mut()?.1.push(c),
+        }
+    }
+    // A leading sign, as in `-1+d6`, leaves the first part empty.
+    if parts.len() > 1 && parts[0].1.is_empty() {
+        parts.remove(0);
+    }
+
+    let mut terms = vec![];
+    let mut dice = 0u64;
+    for (sign, part) in parts {
+        terms.push(match part.split_once('d') {
+            Some((count, sides)) => {
+                let count = if count.is_empty() {
+                    1
+                } else {
+                    count.parse().ok()?
+                };
+                let sides = sides
+                    .parse()
+                    .ok()
+                    .filter(|sides| (1..=i64::MAX as u64).contains(sides))?;
+                dice = dice.saturating_add(count);
+                Term::Dice { count, sides, sign }
+            }
+            None => Term::Constant(sign * part.parse::<i64>().ok()?),
+        });
+    }
+    (dice <= MAX_DRAWS && !terms.is_empty()).then_some(terms)
+}
+
+pub struct Random {
+    mode: Mode,
+    modes: Vec<String>,
+    minimum: String,
+    maximum: String,
+    count: String,
+    dice: String,
+    items: String,
+    seed: String,
+    result: Option<(String, String)>,
+}
+
+impl Default for Random {
+    fn default() -> Self {
+        Self {
+            mode: Mode::default(),
+            modes: Mode::ALL.iter().map(Mode::label).collect(),
+            minimum: String::from("1"),
+            maximum: String::from("100"),
+            count: String::from("1"),
+            dice: String::from("3d6"),
+            items: String::new(),
+            seed: String::new(),
+            result: None,
+        }
+    }
+}
+
+impl Random {
+    pub fn update(&mut self, message: Message, decimal_comma: bool) -> Action<Message> {
+        match message {
+            Message::Mode(index) => self.mode = Mode::ALL[index],
+            Message::Minimum(minimum) => self.minimum = minimum,
+            Message::Maximum(maximum) => self.maximum = maximum,
+            Message::Count(count) => self.count = count,
+            Message::Dice(dice) => self.dice = dice,
+            Message::Items(items) => self.items = items,
+            Message::Seed(seed) => self.seed = seed,
+            Message::Generate => return self.generate(decimal_comma),
+        }
+        Action::None
+    }
+
+    /// The seed to draw with: the one entered, or a fresh one from the clock
+    /// that is then reported so the draw can be repeated.
+    fn seed(&self) -> u64 {
+        let seed = self.seed.trim();
+        if seed.is_empty() {
+            return SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_nanos() as u64);
+        }
+        // Words work as seeds too, hashed with FNV-1a.
+        seed.parse().unwrap_or_else(|_| {
+            seed.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+            })
+        })
+    }
+
+    fn generate(&mut self, decimal_comma: bool) -> Action<Message> {
+        let seed = self.seed();
+        let mut rng = Rng::new(seed);
+        let count = self
+            .count
+            .trim()
+            .parse::<u64>()
+            .ok()
+            .filter(|count| (1..=MAX_DRAWS).contains(count));
+        let items = || {
+            self.items
+                .split(['\n', ',', ';'])
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .collect::<Vec<_>>()
+        };
+
+        let drawn = match self.mode {
+            Mode::Integer => {
+                let (Ok(minimum), Ok(maximum), Some(count)) = (
+                    self.minimum.trim().parse::<i64>(),
+                    self.maximum.trim().parse::<i64>(),
+                    count,
+                ) else {
+                    return Action::Error(EvaluateError::InvalidInput);
+                };
+                let (minimum, maximum) = (minimum.min(maximum), minimum.max(maximum));
+                let values = (0..count)
+                    .map(|_| rng.range(minimum, maximum).to_string())
+                    .collect::<Vec<_>>();
+                (
+                    format!("random({minimum}..{maximum})"),
+                    values.join(", "),
+                    None,
+                )
+            }
+            Mode::Float => {
+                let (Some(minimum), Some(maximum), Some(count)) = (
+                    operations::parse_number(&self.minimum, decimal_comma),
+                    operations::parse_number(&self.maximum, decimal_comma),
+                    count,
+                ) else {
+                    return Action::Error(EvaluateError::InvalidInput);
+                };
+                let values = (0..count)
+                    .map(|_| {
+                        let value = minimum + (maximum - minimum) * rng.unit();
+                        let text = format!("{value:.6}");
+                        if decimal_comma {
+                            text.replace('.', ",")
+                        } else {
+                            text
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                (
+                    format!("random({}..{})", self.minimum.trim(), self.maximum.trim()),
+                    values.join("; "),
+                    None,
+                )
+            }
+            Mode::Dice => {
+                let Some(terms) = parse_dice(&self.dice) else {
+                    return Action::Error(EvaluateError::InvalidInput);
+                };
+                let mut total = 0i64;
+                let mut rolls = vec![];
+                for term in terms {
+                    match term {
+                        Term::Dice { count, sides, sign } => {
+                            for _ in 0..count {
+                                let roll = rng.below(sides) + 1;
+                                rolls.push(roll.to_string());
+                                total = total.saturating_add(sign * roll as i64);
+                            }
+                        }
+                        Term::Constant(constant) => total = total.saturating_add(constant),
+                    }
+                }
+                (
+                    self.dice.trim().to_string(),
+                    total.to_string(),
+                    Some(fl!("rolls", value = rolls.join(", "))),
+                )
+            }
+            Mode::Pick => {
+                let items = items();
+                if items.is_empty() {
+                    return Action::Error(EvaluateError::InvalidInput);
+                }
+                let picked = items[rng.below(items.len() as u64) as usize];
+                (
+                    format!("pick({})", items.join(", ")),
+                    picked.to_string(),
+                    None,
+                )
+            }
+            Mode::Shuffle => {
+                let mut items = items();
+                if items.is_empty() {
+                    return Action::Error(EvaluateError::InvalidInput);
+                }
+                let expression = format!("shuffle({})", items.join(", "));
+                rng.shuffle(&mut items);
+                (expression, items.join(", "), None)
+            }
+        };
+
+        let (expression, outcome, detail) = drawn;
+        let mut details = vec![fl!("seed-used", value = seed.to_string())];
+        details.extend(detail);
+        self.result = Some((outcome.clone(), details.join("\n")));
+        Action::History(Calculator {
+            expression,
+            outcome,
+            decimal_comma,
+            details,
+            unrounded: None,
+        })
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let mut inputs = widget::settings::section().add(widget::settings::item(
+            fl!("mode"),
+            widget::dropdown(
+                &self.modes,
+                Mode::ALL.iter().position(|mode| *mode == self.mode),
+                Message::Mode,
+            ),
+        ));
+        inputs = match self.mode {
+            Mode::Integer | Mode::Float => inputs
+                .add(widget::settings::item(
+                    fl!("minimum"),
+                    widget::text_input("", &self.minimum)
+                        .on_input(Message::Minimum)
+                        .on_submit(|_| Message::Generate),
+                ))
+                .add(widget::settings::item(
+                    fl!("maximum"),
+                    widget::text_input("", &self.maximum)
+                        .on_input(Message::Maximum)
+                        .on_submit(|_| Message::Generate),
+                ))
+                .add(widget::settings::item(
+                    fl!("count"),
+                    widget::text_input("1", &self.count)
+                        .on_input(Message::Count)
+                        .on_submit(|_| Message::Generate),
+                )),
+            Mode::Dice => inputs.add(widget::settings::item(
+                fl!("dice"),
+                widget::text_input("3d6+2", &self.dice)
+                    .on_input(Message::Dice)
+                    .on_submit(|_| Message::Generate),
+            )),
+            Mode::Pick | Mode::Shuffle => inputs.add(widget::settings::item(
+                fl!("items"),
+                widget::text_input(fl!("items-placeholder"), &self.items)
+                    .on_input(Message::Items)
+                    .on_submit(|_| Message::Generate),
+            )),
+        };
+        inputs = inputs.add(
+            widget::settings::item::builder(fl!("seed"))
+                .description(fl!("seed-description"))
+                .control(
+                    widget::text_input("", &self.seed)
+                        .on_input(Message::Seed)
+                        .on_submit(|_| Message::Generate),
+                ),
+        );
+
+        let mut column = vec![
+            inputs.into(),
+            widget::button::suggested(fl!("generate"))
+                .on_press(Message::Generate)
+                .into(),
+        ];
+        if let Some((outcome, details)) = &self.result {
+            column.push(
+                widget::settings::section()
+                    .title(fl!("result"))
+                    .add(widget::text::heading(outcome.as_str()))
+                    .add(widget::text::caption(details.as_str()))
+                    .into(),
+            );
+        }
+        widget::settings::view_column(column).into()
+    }
+}
@@ -0,0 +1,326 @@
+use cosmic::{
+    Element,
+    iced::{Alignment, Length},
+    widget,
+};
+
+use crate::app::{
+    operations::{self, Calculator, EvaluateError},
+    tool::Action,
+};
+use crate::fl;
+
+/// Common aspect ratios offered as presets.
+const PRESETS: [(u64, u64); 5] = [(16, 9), (4, 3), (3, 2), (1, 1), (21, 9)];
+const TERMS: [&str; 4] = ["a", "b", "c", "d"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dimension {
+    Width,
+    Height,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Term(usize, String),
+    Inverse(bool),
+    Solve,
+    Clear,
+    Width(String),
+    Height(String),
+    Target(Dimension, String),
+    Preset(usize),
+    Insert(String),
+}
+
+#[derive(Default)]
+pub struct Ratio {
+    terms: [String; 4],
+    inverse: bool,
+    width: String,
+    height: String,
+    target_width: String,
+    target_height: String,
+    /// The target dimension the user typed; the other one is computed.
+    scale_by: Option<Dimension>,
+}
+
+/// Solves `a : b = c : d` for the term at `unknown`, or `a · b = c · d` when
+/// the quantities are inversely proportional.
+pub fn solve(terms: [f64; 4], unknown: usize, inverse: bool) -> Option<f64> {
+    let [a, b, c, d] = terms;
+    let value = match (unknown, inverse) {
+        (0, false) => b * c / d,
+        (1, false) => a * d / c,
+        (2, false) => a * d / b,
+        (3, false) => b * c / a,
+        (0, true) => c * d / b,
+        (1, true) => c * d / a,
+        (2, true) => a * b / d,
+        (3, true) => a * b / c,
+        _ => return None,
+    };
+    value.is_finite().then_some(value)
+}
+
+/// Reduces `width : height` to lowest terms when both are whole numbers.
+pub fn reduce(width: f64, height: f64) -> Option<(u64, u64)> {
+    if width <= 0.0 || height <= 0.0 || width.fract() != 0.0 || height.fract() != 0.0 {
+        return None;
+    }
+    let (width, height) = (width as u64, height as u64);
+    let divisor = gcd(width, height);
+    Some((width / divisor, height / divisor))
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+impl Ratio {
+    pub fn update(&mut self, message: Message, decimal_comma: bool) -> Action<Message> {
+        match message {
+            Message::Term(index, value) => self.terms[index] = value,
+            Message::Inverse(inverse) => self.inverse = inverse,
+            Message::Solve => return self.solve(decimal_comma),
+            Message::Clear => self.terms = Default::default(),
+            Message::Width(width) => {
+                self.width = width;
+                self.scale(decimal_comma);
+            }
+            Message::Height(height) => {
+                self.height = height;
+                self.scale(decimal_comma);
+            }
+            Message::Target(dimension, value) => {
+                match dimension {
+                    Dimension::Width => self.target_width = value,
+                    Dimension::Height => self.target_height = value,
+                }
+                self.scale_by = Some(dimension);
+                self.scale(decimal_comma);
+            }
+            Message::Preset(index) => {
+                if let Some((width, height)) = PRESETS.get(index) {
+                    self.width = width.to_string();
+                    self.height = height.to_string();
+                    self.scale(decimal_comma);
+                }
+            }
+            Message::Insert(value) => return Action::Insert(value),
+        }
+        Action::None
+    }
+
+    /// Fills in the one empty term and keeps the proportion in the history.
+    fn solve(&mut self, decimal_comma: bool) -> Action<Message> {
+        let empty = self
+            .terms
+            .iter()
+            .enumerate()
+            .filter(|(_, term)| term.trim().is_empty())
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        let [unknown] = empty[..] else {
+            return Action::Error(EvaluateError::InvalidInput);
+        };
+
+        let mut terms = [0.0; 4];
+        for (index, term) in self.terms.iter().enumerate() {
+            if index != unknown {
+                let Some(value) = operations::parse_number(term, decimal_comma) else {
+                    return Action::Error(EvaluateError::InvalidInput);
+                };
+                terms[index] = value;
+            }
+        }
+        let Some(value) = solve(terms, unknown, self.inverse) else {
+            return Action::Error(EvaluateError::InvalidInput);
+        };
+        // Round away float noise such as 2.9999999999999996.
+        let value = format!("{value:.12e}").parse().unwrap_or(value);
+        let outcome = operations::number_literal(value, decimal_comma);
+
+        let term = |index: usize| {
+            if index == unknown {
+                String::from("x")
+            } else {
+                self.terms[index].trim().to_string()
+            }
+        };
+        let joiner = if self.inverse { "·" } else { ":" };
+        let expression = format!(
+            "{} {joiner} {} = {} {joiner} {}",
+            term(0),
+            term(1),
+            term(2),
+            term(3)
+        );
+        let details = if self.inverse {
+            vec![fl!("inverse-proportion")]
+        } else {
+            vec![]
+        };
+
+        self.terms[unknown] = outcome.clone();
+        Action::History(Calculator {
+            expression,
+            outcome,
+            decimal_comma,
+            details,
+            unrounded: None,
+        })
+    }
+
+    /// Computes the target dimension the user did not type, keeping the
+    /// aspect ratio of width and height.
+    fn scale(&mut self, decimal_comma: bool) {
+        let parse = |text: &str| operations::parse_number(text, decimal_comma);
+        let (Some(width), Some(height), Some(scale_by)) =
+            (parse(&self.width), parse(&self.height), self.scale_by)
+        else {
+            return;
+        };
+        if width == 0.0 || height == 0.0 {
+            return;
+        }
+
+        let (known, computed, factor) = match scale_by {
+            Dimension::Width => (&self.target_width, &mut self.target_height, height / width),
+            Dimension::Height => (&self.target_height, &mut self.target_width, width / height),
+        };
+        *computed = match operations::parse_number(known, decimal_comma) {
+            Some(target) => dimension(target * factor, decimal_comma),
+            None => String::new(),
+        };
+    }
+
+    pub fn view(&self, decimal_comma: bool) -> Element<'_, Message> {
+        let spacing = cosmic::theme::active().cosmic().spacing;
+
+        let mut terms = widget::row::with_capacity(7)
+            .spacing(spacing.space_xxs)
+            .align_y(Alignment::Center);
+        for (index, term) in self.terms.iter().enumerate() {
+            terms = terms.push(
+                widget::text_input(TERMS[index], term)
+                    .on_input(move |value| Message::Term(index, value))
+                    .on_submit(|_| Message::Solve)
+                    .width(Length::Fill),
+            );
+            if index < 3 {
+                let joiner = match (index, self.inverse) {
+                    (1, _) => "=",
+                    (_, true) => "·",
+                    (_, false) => ":",
+                };
+                terms = terms.push(widget::text::body(joiner));
+            }
+        }
+        let proportion = widget::settings::section()
+            .title(fl!("proportion"))
+            .add(terms)
+            .add(
+                widget::settings::item::builder(fl!("inverse-proportion"))
+                    .description(fl!("inverse-proportion-description"))
+                    .toggler(self.inverse, Message::Inverse),
+            );
+        let controls = widget::row::with_capacity(2)
+            .push(widget::button::suggested(fl!("solve")).on_press(Message::Solve))
+            .push(widget::button::standard(fl!("clear")).on_press(Message::Clear))
+            .spacing(spacing.space_xxs);
+
+        let presets = PRESETS
+            .iter()
+            .enumerate()
+            .fold(
+                widget::row::with_capacity(PRESETS.len()),
+                |row, (index, (width, height))| {
+                    row.push(
+                        widget::button::standard(format!("{width}:{height}"))
+                            .on_press(Message::Preset(index)),
+                    )
+                },
+            )
+            .spacing(spacing.space_xxs);
+
+        let parse = |text: &str| operations::parse_number(text, decimal_comma);
+        let ratio = match (parse(&self.width), parse(&self.height)) {
+            (Some(width), Some(height)) if width > 0.0 && height > 0.0 => {
+                let decimal = dimension(width / height, decimal_comma);
+                Some(match reduce(width, height) {
+                    Some((width, height)) => format!("{width}:{height} ({decimal}:1)"),
+                    None => format!("{decimal}:1"),
+                })
+            }
+            _ => None,
+        };
+
+        let mut aspect = widget::settings::section()
+            .title(fl!("aspect-ratio"))
+            .add(presets)
+            .add(widget::settings::item(
+                fl!("width"),
+                widget::text_input("", &self.width)
+                    .on_input(Message::Width)
+                    .width(Length::Fixed(140.0)),
+            ))
+            .add(widget::settings::item(
+                fl!("height"),
+                widget::text_input("", &self.height)
+                    .on_input(Message::Height)
+                    .width(Length::Fixed(140.0)),
+            ));
+        if let Some(ratio) = ratio {
+            aspect = aspect.add(widget::settings::item(
+                fl!("ratio"),
+                widget::text::heading(ratio),
+            ));
+        }
+        aspect = aspect
+            .add(widget::settings::item(
+                fl!("target-width"),
+                self.target(Dimension::Width),
+            ))
+            .add(widget::settings::item(
+                fl!("target-height"),
+                self.target(Dimension::Height),
+            ));
+
+        widget::settings::view_column(vec![
+            widget::text::caption(fl!("proportion-description")).into(),
+            proportion.into(),
+            controls.into(),
+            aspect.into(),
+        ])
+        .into()
+    }
+
+    fn target(&self, dimension: Dimension) -> Element<'_, Message> {
+        let spacing = cosmic::theme::active().cosmic().spacing;
+        let value = match dimension {
+            Dimension::Width => &self.target_width,
+            Dimension::Height => &self.target_height,
+        };
+        widget::row::with_capacity(2)
+            .push(
+                widget::text_input("", value)
+                    .on_input(move |value| Message::Target(dimension, value))
+                    .width(Length::Fixed(140.0)),
+            )
+            .push(widget::button::standard(fl!("insert")).on_press_maybe(
+                (!value.trim().is_empty()).then(|| Message::Insert(value.trim().to_string())),
+            ))
+            .spacing(spacing.space_xxs)
+            .align_y(Alignment::Center)
+            .into()
+    }
+}
+
+/// Formats a dimension or ratio with at most two decimals.
+fn dimension(value: f64, decimal_comma: bool) -> String {
+    operations::number_literal((value * 100.0).round() / 100.0, decimal_comma)
+}
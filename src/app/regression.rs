@@ -0,0 +1,525 @@
+use cosmic::{
+    Element,
+    iced::{Length, widget::text_editor},
+    widget,
+};
+
+use crate::app::{
+    operations::{self, Calculator, EvaluateError},
+    tool::Action,
+};
+use crate::fl;
+
+const MAX_DEGREE: usize = 6;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Model {
+    #[default]
+    Linear,
+    Polynomial,
+    Exponential,
+    Logarithmic,
+    Power,
+}
+
+impl Model {
+    const ALL: [Self; 5] = [
+        Self::Linear,
+        Self::Polynomial,
+        Self::Exponential,
+        Self::Logarithmic,
+        Self::Power,
+    ];
+
+    fn label(&self) -> String {
+        match self {
+            Self::Linear => fl!("linear"),
+            Self::Polynomial => fl!("polynomial"),
+            Self::Exponential => fl!("exponential"),
+            Self::Logarithmic => fl!("logarithmic"),
+            Self::Power => fl!("power"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Data(text_editor::Action),
+    Model(usize),
+    Degree(String),
+    Fit,
+    X(String),
+    Predict,
+}
+
+/// A fitted model: its coefficients and how well it explains the data.
+#[derive(Debug, Clone, PartialEq)]
+struct Fit {
+    model: Model,
+    /// `[a, b]` for the two-parameter models, `c₀…cₙ` for polynomials.
+    coefficients: Vec<f64>,
+    r_squared: f64,
+}
+
+impl Fit {
+    fn predict(&self, x: f64) -> f64 {
+        let c = &self.coefficients;
+        match self.model {
+            Model::Linear | Model::Polynomial => c.iter().rev().fold(0.0, |acc, c| acc * x + c),
+            Model::Exponential => c[0] * (c[1] * x).exp(),
+            Model::Logarithmic => c[0] + c[1] * x.ln(),
+            Model::Power => c[0] * x.powf(c[1]),
+        }
+    }
+
+    /// The fitted equation in qalc syntax, in terms of `x`.
+    fn equation(&self, decimal_comma: bool) -> String {
+        let number = |value: f64| operations::number_literal(round(value), decimal_comma);
+        let c = &self.coefficients;
+        match self.model {
+            Model::Linear | Model::Polynomial => {
+                let mut equation = number(c[0]);
+                for (power, coefficient) in c.iter().enumerate().skip(1) {
+                    let sign = if *coefficient < 0.0 { '-' } else { '+' };
+                    equation.push_str(&format!(" {sign} {}*x", number(coefficient.abs())));
+                    if power > 1 {
+                        equation.push_str(&format!("^{power}"));
+                    }
+                }
+                equation
+            }
+            Model::Exponential => format!("{}*e^({}*x)", number(c[0]), number(c[1])),
+            Model::Logarithmic => {
+                let sign = if c[1] < 0.0 { '-' } else { '+' };
+                format!("{} {sign} {}*ln(x)", number(c[0]), number(c[1].abs()))
+            }
+            Model::Power => format!("{}*x^{}", number(c[0]), number(c[1])),
+        }
+    }
+}
+
+pub struct Regression {
+    data: text_editor::Content,
+    model: Model,
+    models: Vec<String>,
+    degree: String,
+    fit: Option<Fit>,
+    equation: String,
+    x: String,
+    prediction: Option<String>,
+}
+
+impl Default for Regression {
+    fn default() -> Self {
+        Self {
+            data: text_editor::Content::new(),
+            model: Model::default(),
+            models: Model::ALL.iter().map(Model::label).collect(),
+            degree: String::from("2"),
+            fit: None,
+            equation: String::new(),
+            x: String::new(),
+            prediction: None,
+        }
+    }
+}
+
+impl Regression {
+    pub fn update(&mut self, message: Message, decimal_comma: bool) -> Action<Message> {
+        match message {
+            Message::Data(action) => self.data.perform(action),
+            Message::Model(index) => self.model = Model::ALL[index],
+            Message::Degree(degree) => self.degree = degree,
+            Message::X(x) => self.x = x,
+            Message::Fit => {
+                let points = match parse_data(&self.data.text(), decimal_comma) {
+                    Ok(points) => points,
+                    Err(err) => return Action::Error(err),
+                };
+                let degree = match self.model {
+                    Model::Polynomial => match self.degree.trim().parse() {
+                        Ok(degree) if (1..=MAX_DEGREE).contains(&degree) => degree,
+                        _ => return Action::Error(EvaluateError::InvalidInput),
+                    },
+                    _ => 1,
+                };
+                let Some(fit) = fit(self.model, degree, &points) else {
+                    return Action::Error(EvaluateError::InvalidInput);
+                };
+
+                let equation = fit.equation(decimal_comma);
+                let r_squared = format!("R² = {}", round(fit.r_squared));
+                self.fit = Some(fit);
+                self.equation = equation.clone();
+                self.prediction = None;
+
+                return Action::History(Calculator {
+                    expression: format!("y = {equation}"),
+                    outcome: equation,
+                    decimal_comma,
+                    details: vec![r_squared],
+                    unrounded: None,
+                });
+            }
+            Message::Predict => {
+                let x = self.x.trim();
+                if self.fit.is_none() || x.is_empty() {
+                    return Action::None;
+                }
+                // Route through qalc so x may itself be an expression.
+                let equation = operations::substitute(&self.equation, "x", x);
+                match operations::evaluate(&equation, decimal_comma) {
+                    Ok(y) => self.prediction = Some(y),
+                    Err(err) => return Action::Error(err),
+                }
+            }
+        }
+        Action::None
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let spacing = cosmic::theme::active().cosmic().spacing;
+
+        let mut model =
+            widget::settings::section()
+                .title(fl!("model"))
+                .add(widget::settings::item(
+                    fl!("model"),
+                    widget::dropdown(
+                        &self.models,
+                        Model::ALL.iter().position(|m| m == &self.model),
+                        Message::Model,
+                    ),
+                ));
+        if self.model == Model::Polynomial {
+            model = model.add(widget::settings::item(
+                fl!("degree"),
+                widget::text_input("2", &self.degree).on_input(Message::Degree),
+            ));
+        }
+
+        let data = widget::column::with_capacity(2)
+            .push(widget::text::heading(fl!("data")))
+            .push(
+                text_editor(&self.data)
+                    .placeholder(fl!("data-placeholder"))
+                    .on_action(Message::Data)
+                    .height(Length::Fixed(160.0)),
+            )
+            .spacing(spacing.space_xxs);
+
+        let mut children = vec![
+            data.into(),
+            model.into(),
+            widget::button::suggested(fl!("fit"))
+                .on_press(Message::Fit)
+                .into(),
+        ];
+
+        if let Some(fit) = &self.fit {
+            let mut result =
+                widget::settings::section()
+                    .title(fl!("result"))
+                    .add(widget::settings::item(
+                        fl!("equation"),
+                        widget::text::body(format!("y = {}", self.equation)),
+                    ));
+            let names = ["a", "b"];
+            for (index, coefficient) in fit.coefficients.iter().enumerate() {
+                let name = match fit.model {
+                    Model::Linear | Model::Polynomial => format!("c{index}"),
+                    _ => names[index].to_string(),
+                };
+                result = result.add(widget::settings::item(
+                    name,
+                    widget::text::body(round(*coefficient).to_string()),
+                ));
+            }
+            result = result
+                .add(widget::settings::item(
+                    "R²",
+                    widget::text::body(round(fit.r_squared).to_string()),
+                ))
+                .add(widget::settings::item(
+                    fl!("predict-y"),
+                    widget::text_input("x", &self.x)
+                        .on_input(Message::X)
+                        .on_submit(|_| Message::Predict),
+                ));
+            if let Some(prediction) = &self.prediction {
+                result = result.add(widget::settings::item(
+                    "y",
+                    widget::text::body(prediction.as_str()),
+                ));
+            }
+            children.push(result.into());
+        }
+
+        widget::settings::view_column(children).into()
+    }
+}
+
+/// Reads `x y` pairs separated by tabs, commas, semicolons or spaces.
+///
+/// Commas only separate columns when they aren't the decimal separator. Lines
+/// without digits, such as headers, are skipped. Cells follow the same rules as
+/// typed input and anything that isn't a plain number is evaluated by qalc.
+fn parse_data(text: &str, decimal_comma: bool) -> Result<Vec<(f64, f64)>, EvaluateError> {
+    let is_separator =
+        |c: char| c == '\t' || c == ';' || c.is_whitespace() || (c == ',' && !decimal_comma);
+
+    let mut cells = vec![];
+    for line in text.lines() {
+        if !line.chars().any(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        let row = line
+            .split(is_separator)
+            .filter(|cell| !cell.is_empty())
+            .collect::<Vec<_>>();
+        if row.len() != 2
+            || !row
+                .iter()
+                .all(|cell| cell.chars().all(operations::is_expression_char))
+        {
+            return Err(EvaluateError::InvalidInput);
+        }
+        cells.extend(row.into_iter().map(String::from));
+    }
+    if cells.is_empty() {
+        return Err(EvaluateError::Empty);
+    }
+
+    let mut values = cells
+        .iter()
+        .map(|cell| operations::parse_number(cell, decimal_comma))
+        .collect::<Vec<_>>();
+    let pending = cells
+        .iter()
+        .zip(&values)
+        .filter(|(_, value)| value.is_none())
+        .map(|(cell, _)| cell.clone())
+        .collect::<Vec<_>>();
+    if !pending.is_empty() {
+        let mut results = operations::evaluate_batch(&pending, decimal_comma, &[]).into_iter();
+        for value in values.iter_mut().filter(|value| value.is_none()) {
+            *value = results
+                .next()
+                .and_then(|result| result.ok())
+                .and_then(|result| operations::parse_number(&result, decimal_comma));
+        }
+    }
+
+    values
+        .chunks(2)
+        .map(|pair| match pair {
+            [Some(x), Some(y)] => Ok((*x, *y)),
+            _ => Err(EvaluateError::InvalidInput),
+        })
+        .collect()
+}
+
+/// Least-squares fit of `model` to `points`; `degree` only applies to polynomials.
+fn fit(model: Model, degree: usize, points: &[(f64, f64)]) -> Option<Fit> {
+    // The linearized models need positive values before taking logarithms.
+    let transformed = points
+        .iter()
+        .map(|&(x, y)| match model {
+            Model::Linear | Model::Polynomial => Some((x, y)),
+            Model::Exponential => (y > 0.0).then(|| (x, y.ln())),
+            Model::Logarithmic => (x > 0.0).then(|| (x.ln(), y)),
+            Model::Power => (x > 0.0 && y > 0.0).then(|| (x.ln(), y.ln())),
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let degree = if model == Model::Polynomial {
+        degree
+    } else {
+        1
+    };
+    let mut coefficients = polynomial_fit(&transformed, degree)?;
+    if matches!(model, Model::Exponential | Model::Power) {
+        coefficients[0] = coefficients[0].exp();
+    }
+
+    let mut fit = Fit {
+        model,
+        coefficients,
+        r_squared: 0.0,
+    };
+
+    let mean = points.iter().map(|(_, y)| y).sum::<f64>() / points.len() as f64;
+    let total = points.iter().map(|(_, y)| (y - mean).powi(2)).sum::<f64>();
+    let residual = points
+        .iter()
+        .map(|&(x, y)| (y - fit.predict(x)).powi(2))
+        .sum::<f64>();
+    fit.r_squared = if total == 0.0 {
+        1.0
+    } else {
+        1.0 - residual / total
+    };
+
+    fit.coefficients
+        .iter()
+        .all(|c| c.is_finite())
+        .then_some(fit)
+}
+
+/// Solves the normal equations for `c₀ + c₁x + … + cₙxⁿ`.
+///
+/// x is centred and scaled to [-1, 1] first, so the powers stay comparable
+/// in size whether x is around 1e-4 or 1e6, and the coefficients are then
+/// expanded back to powers of x.
+fn polynomial_fit(points: &[(f64, f64)], degree: usize) -> Option<Vec<f64>> {
+    let size = degree + 1;
+    if points.len() < size {
+        return None;
+    }
+
+    let mean = points.iter().map(|&(x, _)| x).sum::<f64>() / points.len() as f64;
+    let scale = points
+        .iter()
+        .map(|&(x, _)| (x - mean).abs())
+        .fold(0.0, f64::max);
+    if scale == 0.0 {
+        return None;
+    }
+
+    // Augmented matrix [UᵀU | Uᵀy] in u = (x - mean) / scale.
+    let mut matrix = vec![vec![0.0; size + 1]; size];
+    for &(x, y) in points {
+        let u = (x - mean) / scale;
+        let powers = (0..=2 * degree)
+            .map(|p| u.powi(p as i32))
+            .collect::<Vec<_>>();
+        for row in 0..size {
+            for column in 0..size {
+                matrix[row][column] += powers[row + column];
+            }
+            matrix[row][size] += powers[row] * y;
+        }
+    }
+
+    // Gaussian elimination with partial pivoting. A pivot is negligible
+    // relative to the point count, the largest entry once u is scaled.
+    let tolerance = 1e-12 * points.len() as f64;
+    for column in 0..size {
+        let pivot = (column..size)
+            .max_by(|&a, &b| matrix[a][column].abs().total_cmp(&matrix[b][column].abs()))?;
+        if matrix[pivot][column].abs() < tolerance {
+            return None;
+        }
+        matrix.swap(column, pivot);
+        for row in column + 1..size {
+            let factor = matrix[row][column] / matrix[column][column];
+            for k in column..=size {
+                matrix[row][k] -= factor * matrix[column][k];
+            }
+        }
+    }
+
+    let mut scaled = vec![0.0; size];
+    for row in (0..size).rev() {
+        let sum = (row + 1..size)
+            .map(|k| matrix[row][k] * scaled[k])
+            .sum::<f64>();
+        scaled[row] = (matrix[row][size] - sum) / matrix[row][row];
+    }
+
+    // Expand each cₖ((x - mean) / scale)ᵏ with the binomial theorem.
+    let mut coefficients = vec![0.0; size];
+    for (k, c) in scaled.iter().enumerate() {
+        let c = c / scale.powi(k as i32);
+        let mut binomial = 1.0;
+        for j in 0..=k {
+            coefficients[j] += c * binomial * (-mean).powi((k - j) as i32);
+            binomial = binomial * (k - j) as f64 / (j + 1) as f64;
+        }
+    }
+    Some(coefficients)
+}
+
+/// Rounds to ten significant digits to hide floating point noise.
+fn round(value: f64) -> f64 {
+    format!("{value:.9e}").parse().unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(actual: f64, expected: f64, tolerance: f64) -> bool {
+        (actual - expected).abs() <= tolerance * expected.abs().max(1.0)
+    }
+
+    #[test]
+    fn exact_quadratic() {
+        let points = (0..7)
+            .map(|x| {
+                let x = f64::from(x);
+                (x, 2.0 - 3.0 * x + 0.5 * x * x)
+            })
+            .collect::<Vec<_>>();
+        let coefficients = polynomial_fit(&points, 2).unwrap();
+        for (actual, expected) in coefficients.iter().zip([2.0, -3.0, 0.5]) {
+            assert!(close(*actual, expected, 1e-9), "{coefficients:?}");
+        }
+    }
+
+    #[test]
+    fn years_as_x() {
+        // Raw powers of 2000…2020 differ by twelve orders of magnitude.
+        let curve = |x: f64| 3.0 + 0.25 * (x - 2000.0) - 0.01 * (x - 2000.0).powi(2);
+        let points = (2000..=2020)
+            .map(|year| (f64::from(year), curve(f64::from(year))))
+            .collect::<Vec<_>>();
+        let quadratic = fit(Model::Polynomial, 2, &points).unwrap();
+        assert!(
+            close(quadratic.r_squared, 1.0, 1e-9),
+            "{}",
+            quadratic.r_squared
+        );
+        for year in [2000.0, 2010.5, 2020.0, 2025.0] {
+            assert!(close(quadratic.predict(year), curve(year), 1e-6), "{year}");
+        }
+
+        let cubic = |x: f64| 1.0 + 1e-3 * (x - 2010.0).powi(3);
+        let points = (2000..=2020)
+            .map(|year| (f64::from(year), cubic(f64::from(year))))
+            .collect::<Vec<_>>();
+        let fitted = fit(Model::Polynomial, 3, &points).unwrap();
+        assert!(close(fitted.predict(2015.0), cubic(2015.0), 1e-6));
+    }
+
+    #[test]
+    fn small_x() {
+        let points = (1..=5)
+            .map(|i| {
+                let x = f64::from(i) * 1e-4;
+                (x, 1.0 + 2e4 * x)
+            })
+            .collect::<Vec<_>>();
+        let coefficients = polynomial_fit(&points, 1).unwrap();
+        assert!(close(coefficients[0], 1.0, 1e-9), "{coefficients:?}");
+        assert!(close(coefficients[1], 2e4, 1e-9), "{coefficients:?}");
+    }
+
+    #[test]
+    fn degree_needs_enough_points() {
+        let points = [(1.0, 1.0), (2.0, 4.0), (3.0, 9.0)];
+        assert_eq!(polynomial_fit(&points, 3), None);
+        assert_eq!(polynomial_fit(&points, 5), None);
+        // As many coefficients as points interpolates exactly.
+        let coefficients = polynomial_fit(&points, 2).unwrap();
+        for (actual, expected) in coefficients.iter().zip([0.0, 0.0, 1.0]) {
+            assert!(close(*actual, expected, 1e-9), "{coefficients:?}");
+        }
+    }
+
+    #[test]
+    fn repeated_x_cannot_be_fitted() {
+        let points = [(2.0, 1.0), (2.0, 3.0), (2.0, 5.0)];
+        assert_eq!(polynomial_fit(&points, 1), None);
+    }
+}
@@ -0,0 +1,155 @@
+use crate::app::{
+    operations::{self, Calculator, EvaluateError},
+    operator::Operator,
+};
+
+/// Names of the four classic stack registers, from X upwards.
+const REGISTERS: [&str; 4] = ["X", "Y", "Z", "T"];
+
+/// An RPN stack whose last element is X. It grows without limit; the
+/// classic X/Y/Z/T registers are its lowest four levels.
+#[derive(Debug, Default, Clone)]
+pub struct Rpn {
+    pub stack: Vec<String>,
+    /// X before the last operation that consumed it.
+    pub last_x: Option<String>,
+}
+
+impl Rpn {
+    /// Applies a stack key. `entry` is the number being typed, if any, which
+    /// is pushed first as an implicit ENTER. Returns the calculation to keep
+    /// in the history when the key computed a result.
+    pub fn apply(
+        &mut self,
+        operator: &Operator,
+        entry: &str,
+        decimal_comma: bool,
+    ) -> Result<Option<Calculator>, EvaluateError> {
+        let entry = entry.trim();
+        if !entry.is_empty() {
+            self.stack.push(operations::evaluate(entry, decimal_comma)?);
+            if matches!(operator, Operator::Equal | Operator::Enter) {
+                return Ok(None);
+            }
+        }
+
+        match operator {
+            Operator::Equal | Operator::Enter => {
+                if let Some(x) = self.stack.last().cloned() {
+                    self.stack.push(x);
+                }
+                Ok(None)
+            }
+            Operator::Swap => {
+                let len = self.stack.len();
+                if len >= 2 {
+                    self.stack.swap(len - 1, len - 2);
+                }
+                Ok(None)
+            }
+            // R↓: X moves to the top of the stack and everything else drops.
+            Operator::Roll => {
+                self.stack.rotate_right(1.min(self.stack.len()));
+                Ok(None)
+            }
+            Operator::Drop => {
+                self.stack.pop();
+                Ok(None)
+            }
+            Operator::LastX => {
+                if let Some(last_x) = self.last_x.clone() {
+                    self.stack.push(last_x);
+                }
+                Ok(None)
+            }
+            Operator::Clear => {
+                self.stack.clear();
+                Ok(None)
+            }
+            Operator::Negate => self.unary(|x| format!("-({x})"), decimal_comma),
+            Operator::SquareRoot => self.unary(|x| format!("√({x})"), decimal_comma),
+            // y stays on the stack and x becomes x percent of y.
+            Operator::Percent => {
+                let len = self.stack.len();
+                if len < 2 {
+                    return Ok(None);
+                }
+                let (y, x) = (&self.stack[len - 2], &self.stack[len - 1]);
+                let expression = format!("({y})*({x})/100");
+                let outcome = operations::evaluate(&expression, decimal_comma)?;
+                self.last_x = self.stack.pop();
+                self.stack.push(outcome.clone());
+                Ok(Some(entry_for(expression, outcome, decimal_comma)))
+            }
+            operator if Operator::BINARY.contains(operator) => {
+                let len = self.stack.len();
+                if len < 2 {
+                    return Ok(None);
+                }
+                let (y, x) = (&self.stack[len - 2], &self.stack[len - 1]);
+                let expression = format!("{}{}{}", operand(y), operator.expression(), operand(x));
+                let outcome = operations::evaluate(&expression, decimal_comma)?;
+                self.last_x = self.stack.pop();
+                self.stack.pop();
+                self.stack.push(outcome.clone());
+                Ok(Some(entry_for(expression, outcome, decimal_comma)))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn unary(
+        &mut self,
+        expression: impl Fn(&str) -> String,
+        decimal_comma: bool,
+    ) -> Result<Option<Calculator>, EvaluateError> {
+        let Some(x) = self.stack.last() else {
+            return Ok(None);
+        };
+        let expression = expression(x);
+        let outcome = operations::evaluate(&expression, decimal_comma)?;
+        self.last_x = self.stack.pop();
+        self.stack.push(outcome.clone());
+        Ok(Some(entry_for(expression, outcome, decimal_comma)))
+    }
+
+    /// The stack levels to display, from the highest down to X, with their
+    /// labels. At least the four classic registers are always shown.
+    pub fn levels(&self) -> Vec<(String, &str)> {
+        let len = self.stack.len();
+        (0..len.max(REGISTERS.len()))
+            .rev()
+            .map(|level| {
+                let label = REGISTERS
+                    .get(level)
+                    .map_or_else(|| (level + 1).to_string(), |name| name.to_string());
+                let value = len
+                    .checked_sub(level + 1)
+                    .map_or("", |index| self.stack[index].as_str());
+                (label, value)
+            })
+            .collect()
+    }
+}
+
+/// Parenthesizes negative and compound values so they stay one operand.
+fn operand(value: &str) -> String {
+    if value
+        .chars()
+        .all(|c| c.is_ascii_digit() || c == '.' || c == ',')
+    {
+        value.to_string()
+    } else {
+        format!("({value})")
+    }
+}
+
+fn entry_for(expression: String, outcome: String, decimal_comma: bool) -> Calculator {
+    Calculator {
+        expression,
+        outcome,
+        decimal_comma,
+        details: vec![String::from("RPN")],
+        unrounded: None,
+    }
+}
@@ -0,0 +1,283 @@
+use cosmic::{
+    Element,
+    iced::{Length, Task},
+    widget,
+};
+
+use crate::app::{
+    operations,
+    tool::{self, Action},
+};
+use crate::fl;
+
+/// Upper bound on generated rows, so a tiny step can't stall qalc.
+const MAX_ROWS: usize = 1000;
+const MAX_COLUMNS: usize = 4;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Column(usize, String),
+    AddColumn,
+    RemoveColumn(usize),
+    Start(String),
+    End(String),
+    Step(String),
+    Generate,
+    Generated(u64, Vec<Row>),
+    CopyTsv,
+    ExportCsv,
+}
+
+#[derive(Debug, Clone)]
+pub struct Row {
+    x: String,
+    values: Vec<String>,
+}
+
+pub struct Table {
+    columns: Vec<String>,
+    start: String,
+    end: String,
+    step: String,
+    headers: Vec<String>,
+    rows: Vec<Row>,
+    generation: u64,
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Self {
+            columns: vec![String::new()],
+            start: String::from("0"),
+            end: String::from("10"),
+            step: String::from("1"),
+            headers: vec![],
+            rows: vec![],
+            generation: 0,
+        }
+    }
+}
+
+impl Table {
+    pub fn update(&mut self, message: Message, decimal_comma: bool) -> Action<Message> {
+        match message {
+            Message::Column(index, column) => {
+                if let Some(slot) = self.columns.get_mut(index) {
+                    *slot = column;
+                }
+            }
+            Message::AddColumn => {
+                if self.columns.len() < MAX_COLUMNS {
+                    self.columns.push(String::new());
+                }
+            }
+            Message::RemoveColumn(index) => {
+                if self.columns.len() > 1 {
+                    self.columns.remove(index);
+                }
+            }
+            Message::Start(start) => self.start = start,
+            Message::End(end) => self.end = end,
+            Message::Step(step) => self.step = step,
+            Message::Generate => return self.generate(decimal_comma),
+            Message::Generated(generation, rows) => {
+                if generation == self.generation {
+                    self.rows = rows;
+                }
+            }
+            Message::CopyTsv => {
+                if !self.rows.is_empty() {
+                    return Action::Copy(self.to_delimited("\t", |field| field.to_string()));
+                }
+            }
+            Message::ExportCsv => {
+                if !self.rows.is_empty() {
+                    return Action::Export("table", "csv", self.to_delimited(",", tool::csv_field));
+                }
+            }
+        }
+        Action::None
+    }
+
+    fn generate(&mut self, decimal_comma: bool) -> Action<Message> {
+        let parse = |text: &str| operations::parse_number(text, decimal_comma);
+        let (Some(start), Some(end), Some(step)) =
+            (parse(&self.start), parse(&self.end), parse(&self.step))
+        else {
+            return Action::Error(operations::EvaluateError::InvalidInput);
+        };
+        if step == 0.0 || (end - start) / step < 0.0 {
+            return Action::Error(operations::EvaluateError::InvalidInput);
+        }
+
+        let count = (((end - start) / step).floor() as usize + 1).min(MAX_ROWS);
+        // Round away accumulated float noise such as 0.30000000000000004.
+        let xs = (0..count)
+            .map(|i| {
+                format!("{:.10e}", start + step * i as f64)
+                    .parse()
+                    .unwrap_or(0.0)
+            })
+            .collect::<Vec<f64>>();
+
+        self.headers = self.columns.clone();
+        self.generation += 1;
+        let generation = self.generation;
+        let columns = self.columns.clone();
+
+        Task::perform(
+            async move {
+                let values = columns
+                    .iter()
+                    .map(|column| {
+                        let expressions = xs
+                            .iter()
+                            .map(|x| {
+                                let x = operations::number_literal(*x, decimal_comma);
+                                operations::substitute(column, "x", &x)
+                            })
+                            .collect::<Vec<_>>();
+                        operations::evaluate_batch(&expressions, decimal_comma, &[])
+                            .into_iter()
+                            .map(|result| result.unwrap_or_else(|_| String::from("—")))
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>();
+
+                xs.iter()
+                    .enumerate()
+                    .map(|(i, x)| Row {
+                        x: operations::number_literal(*x, decimal_comma),
+                        values: values.iter().map(|column| column[i].clone()).collect(),
+                    })
+                    .collect()
+            },
+            move |rows| Message::Generated(generation, rows),
+        )
+        .into()
+    }
+
+    /// Serializes the header and rows, escaping each field with `escape`.
+    fn to_delimited(&self, separator: &str, escape: impl Fn(&str) -> String) -> String {
+        let mut text = std::iter::once("x")
+            .chain(self.headers.iter().map(String::as_str))
+            .map(&escape)
+            .collect::<Vec<_>>()
+            .join(separator);
+        text.push('\n');
+        for row in &self.rows {
+            let line = std::iter::once(row.x.as_str())
+                .chain(row.values.iter().map(String::as_str))
+                .map(&escape)
+                .collect::<Vec<_>>()
+                .join(separator);
+            text.push_str(&line);
+            text.push('\n');
+        }
+        text
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let spacing = cosmic::theme::active().cosmic().spacing;
+
+        let mut columns = widget::settings::section().title(fl!("functions"));
+        for (index, column) in self.columns.iter().enumerate() {
+            let mut row = widget::row::with_capacity(2)
+                .push(
+                    widget::text_input(format!("f{}(x)", index + 1), column)
+                        .on_input(move |column| Message::Column(index, column))
+                        .on_submit(|_| Message::Generate)
+                        .width(Length::Fill),
+                )
+                .spacing(spacing.space_xxs)
+                .align_y(cosmic::iced::Alignment::Center);
+            if self.columns.len() > 1 {
+                row = row.push(
+                    widget::button::icon(widget::icon::from_name("list-remove-symbolic"))
+                        .on_press(Message::RemoveColumn(index)),
+                );
+            }
+            columns = columns.add(row);
+        }
+
+        let range = widget::settings::section()
+            .title(fl!("range"))
+            .add(widget::settings::item(
+                fl!("start"),
+                widget::text_input("", &self.start)
+                    .on_input(Message::Start)
+                    .on_submit(|_| Message::Generate),
+            ))
+            .add(widget::settings::item(
+                fl!("end"),
+                widget::text_input("", &self.end)
+                    .on_input(Message::End)
+                    .on_submit(|_| Message::Generate),
+            ))
+            .add(widget::settings::item(
+                fl!("step"),
+                widget::text_input("", &self.step)
+                    .on_input(Message::Step)
+                    .on_submit(|_| Message::Generate),
+            ));
+
+        let has_rows = !self.rows.is_empty();
+        let controls = widget::row::with_capacity(4)
+            .push(widget::button::suggested(fl!("generate")).on_press(Message::Generate))
+            .push(
+                widget::button::standard(fl!("add-function")).on_press_maybe(
+                    (self.columns.len() < MAX_COLUMNS).then_some(Message::AddColumn),
+                ),
+            )
+            .push(
+                widget::button::standard(fl!("copy-tsv"))
+                    .on_press_maybe(has_rows.then_some(Message::CopyTsv)),
+            )
+            .push(
+                widget::button::standard(fl!("export-csv"))
+                    .on_press_maybe(has_rows.then_some(Message::ExportCsv)),
+            )
+            .spacing(spacing.space_xxs);
+
+        let mut table = widget::column::with_capacity(self.rows.len() + 1)
+            .push(
+                widget::row::with_children(
+                    std::iter::once("x")
+                        .chain(self.headers.iter().map(String::as_str))
+                        .map(|header| cell(header, true))
+                        .collect(),
+                )
+                .spacing(spacing.space_xs),
+            )
+            .spacing(spacing.space_xxxs);
+        for row in &self.rows {
+            table = table.push(
+                widget::row::with_children(
+                    std::iter::once(cell(&row.x, false))
+                        .chain(row.values.iter().map(|value| cell(value, false)))
+                        .collect(),
+                )
+                .spacing(spacing.space_xs),
+            );
+        }
+
+        widget::settings::view_column(vec![
+            columns.into(),
+            range.into(),
+            controls.into(),
+            widget::scrollable(table)
+                .height(Length::Fixed(320.0))
+                .into(),
+        ])
+        .into()
+    }
+}
+
+fn cell(text: &str, header: bool) -> Element<'static, Message> {
+    let text = if header {
+        widget::text::heading(text.to_string())
+    } else {
+        widget::text::body(text.to_string())
+    };
+    text.width(Length::FillPortion(1)).into()
+}
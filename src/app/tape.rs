@@ -0,0 +1,482 @@
+use cosmic::{Element, iced::Length, widget};
+
+use crate::app::{
+    display, operations,
+    tool::{self, Action},
+};
+use crate::fl;
+
+/// Lines per printed page, which fits 10pt Courier on A4.
+const LINES_PER_PAGE: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Entry,
+    Subtotal,
+    Total,
+}
+
+impl Kind {
+    /// The mark printed beside the value, as on adding machine tapes.
+    fn marker(&self) -> &'static str {
+        match self {
+            Self::Entry => "",
+            Self::Subtotal => "S",
+            Self::Total => "T",
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Entry => "entry",
+            Self::Subtotal => "subtotal",
+            Self::Total => "total",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Line {
+    operator: String,
+    value: String,
+    kind: Kind,
+    note: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Note(usize, String),
+    ExportText,
+    ExportCsv,
+    Print,
+    Clear,
+}
+
+#[derive(Default)]
+pub struct Tape {
+    lines: Vec<Line>,
+    /// Sum of every total since the tape was cleared.
+    running_total: f64,
+}
+
+impl Tape {
+    pub fn update(&mut self, message: Message, decimal_comma: bool) -> Action<Message> {
+        match message {
+            Message::Note(index, note) => {
+                if let Some(line) = self.lines.get_mut(index) {
+                    line.note = note;
+                }
+            }
+            Message::ExportText => {
+                if !self.lines.is_empty() {
+                    let mut text = self.text_lines(decimal_comma).join("\n");
+                    text.push('\n');
+                    return Action::Export("tape", "txt", text);
+                }
+            }
+            Message::ExportCsv => {
+                if !self.lines.is_empty() {
+                    return Action::Export("tape", "csv", self.to_csv());
+                }
+            }
+            Message::Print => {
+                if !self.lines.is_empty() {
+                    let mut lines = vec![fl!("paper-tape"), String::new()];
+                    lines.extend(self.text_lines(decimal_comma));
+                    return Action::Export("tape", "pdf", pdf(&lines));
+                }
+            }
+            Message::Clear => *self = Self::default(),
+        }
+        Action::None
+    }
+
+    /// Appends the operands and operators of `expression` followed by its
+    /// `result`. A leading operand equal to the previous result is skipped,
+    /// so chained and repeated calculations read as one continuous tape.
+    pub fn record(&mut self, expression: &str, result: &str, kind: Kind, decimal_comma: bool) {
+        let mut entries = entries(expression);
+        if let Some(last) = self.lines.last()
+            && last.kind != Kind::Entry
+            && entries
+                .first()
+                .is_some_and(|(operator, operand)| operator.is_empty() && *operand == last.value)
+        {
+            entries.remove(0);
+        }
+        for (operator, operand) in entries {
+            self.entry(operator, operand);
+        }
+        self.result(result, kind, decimal_comma);
+    }
+
+    pub fn entry(&mut self, operator: impl Into<String>, value: impl Into<String>) {
+        self.lines.push(Line {
+            operator: operator.into(),
+            value: value.into(),
+            kind: Kind::Entry,
+            note: String::new(),
+        });
+    }
+
+    pub fn result(&mut self, value: &str, kind: Kind, decimal_comma: bool) {
+        if kind == Kind::Total
+            && let Some(value) = operations::parse_number(value, decimal_comma)
+        {
+            self.running_total = operations::add_decimals(self.running_total, value);
+        }
+        self.lines.push(Line {
+            operator: String::from("="),
+            value: value.to_string(),
+            kind,
+            note: String::new(),
+        });
+    }
+
+    fn text_lines(&self, decimal_comma: bool) -> Vec<String> {
+        let mut lines = self
+            .lines
+            .iter()
+            .map(|line| {
+                format!(
+                    "{:<4}{:>24} {:<2}{}",
+                    line.operator,
+                    line.value,
+                    line.kind.marker(),
+                    line.note
+                )
+                .trim_end()
+                .to_string()
+            })
+            .collect::<Vec<_>>();
+        lines.push(String::new());
+        lines.push(format!(
+            "{}: {}",
+            fl!("running-total"),
+            operations::number_literal(self.running_total, decimal_comma)
+        ));
+        lines
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("operator,value,kind,note\n");
+        for line in &self.lines {
+            let fields = [
+                line.operator.as_str(),
+                line.value.as_str(),
+                line.kind.name(),
+                line.note.as_str(),
+            ];
+            let line = fields
+                .iter()
+                .map(|field| tool::csv_field(field))
+                .collect::<Vec<_>>()
+                .join(",");
+            csv.push_str(&line);
+            csv.push('\n');
+        }
+        csv
+    }
+
+    pub fn view(&self, decimal_comma: bool) -> Element<'_, Message> {
+        let spacing = cosmic::theme::active().cosmic().spacing;
+
+        let total = widget::settings::section().add(widget::settings::item(
+            fl!("running-total"),
+            widget::text::heading(operations::number_literal(
+                self.running_total,
+                decimal_comma,
+            )),
+        ));
+
+        let has_lines = !self.lines.is_empty();
+        let controls = widget::row::with_capacity(4)
+            .push(
+                widget::button::standard(fl!("export-text"))
+                    .on_press_maybe(has_lines.then_some(Message::ExportText)),
+            )
+            .push(
+                widget::button::standard(fl!("export-csv"))
+                    .on_press_maybe(has_lines.then_some(Message::ExportCsv)),
+            )
+            .push(
+                widget::button::standard(fl!("print-to-file"))
+                    .on_press_maybe(has_lines.then_some(Message::Print)),
+            )
+            .push(
+                widget::button::destructive(fl!("clear"))
+                    .on_press_maybe(has_lines.then_some(Message::Clear)),
+            )
+            .spacing(spacing.space_xxs);
+
+        let mut tape = widget::column::with_capacity(self.lines.len()).spacing(spacing.space_xxxs);
+        for (index, line) in self.lines.iter().enumerate() {
+            let value = if line.kind == Kind::Entry {
+                widget::text::body(line.value.as_str())
+            } else {
+                widget::text::heading(line.value.as_str())
+            };
+            tape = tape.push(
+                widget::row::with_capacity(4)
+                    .push(widget::text::body(line.operator.as_str()).width(Length::Fixed(40.0)))
+                    .push(
+                        value
+                            .width(Length::FillPortion(2))
+                            .align_x(cosmic::iced::Alignment::End),
+                    )
+                    .push(widget::text::caption(line.kind.marker()).width(Length::Fixed(16.0)))
+                    .push(
+                        widget::text_input(fl!("note"), &line.note)
+                            .on_input(move |note| Message::Note(index, note))
+                            .width(Length::FillPortion(3)),
+                    )
+                    .spacing(spacing.space_xxs)
+                    .align_y(cosmic::iced::Alignment::Center),
+            );
+        }
+
+        widget::settings::view_column(vec![
+            total.into(),
+            controls.into(),
+            widget::scrollable(tape).height(Length::Fixed(420.0)).into(),
+        ])
+        .into()
+    }
+}
+
+/// Splits `expression` at its top-level binary operators into
+/// `(operator, operand)` pairs; the first operator is empty.
+fn entries(expression: &str) -> Vec<(String, String)> {
+    let chars = expression.chars().collect::<Vec<_>>();
+    let mut entries = vec![];
+    let mut operator = String::new();
+    let mut operand = String::new();
+    let mut depth = 0;
+    let mut index = 0;
+
+    while index < chars.len() {
+        let c = chars[index];
+        let pending = operand.trim_end();
+        // A sign with no operand before it is unary, as is the one in `1.2E-7`.
+        let binary = depth == 0
+            && !pending.is_empty()
+            && !(matches!(c, '+' | '-')
+                && pending.ends_with(['E', 'e'])
+                && pending[..pending.len() - 1].ends_with(|c: char| c.is_ascii_digit()));
+
+        if binary && chars[index..].starts_with(&[' ', 'm', 'o', 'd', ' ']) {
+            entries.push((operator, operand.trim().to_string()));
+            operator = String::from("mod");
+            operand = String::new();
+            index += 5;
+            continue;
+        }
+
+        let symbol = match c {
+            '+' => Some("+"),
+            '-' | '−' => Some("−"),
+            '*' | '×' => Some("×"),
+            '/' | '÷' => Some("÷"),
+            '^' => Some("^"),
+            _ => None,
+        };
+        match symbol {
+            Some(symbol) if binary => {
+                entries.push((operator, operand.trim().to_string()));
+                operator = symbol.to_string();
+                operand = String::new();
+            }
+            _ => {
+                match c {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    _ => {}
+                }
+                operand.push(c);
+            }
+        }
+        index += 1;
+    }
+
+    if !operand.trim().is_empty() {
+        entries.push((operator, operand.trim().to_string()));
+    }
+    entries
+}
+
+/// Lays `lines` out as a minimal PDF in 10pt Courier on A4 pages. Text is
+/// written with octal escapes, so the whole document is ASCII.
+fn pdf(lines: &[String]) -> String {
+    let pages = lines.chunks(LINES_PER_PAGE).collect::<Vec<_>>();
+    let kids = (0..pages.len())
+        .map(|page| format!("{} 0 R", 4 + 2 * page))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    // Objects 1 to 3 are the catalog, page tree and font; each page then
+    // takes two objects, the page and its content stream.
+    let mut objects = vec![
+        String::from("<< /Type /Catalog /Pages 2 0 R >>"),
+        format!("<< /Type /Pages /Kids [{kids}] /Count {} >>", pages.len()),
+        String::from(
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Courier /Encoding /WinAnsiEncoding >>",
+        ),
+    ];
+    for (page, lines) in pages.iter().enumerate() {
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 595 842] \
+             /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+            5 + 2 * page
+        ));
+        let mut stream = String::from("BT /F1 10 Tf 12 TL 56 800 Td\n");
+        for line in lines.iter() {
+            stream.push_str(&format!("({}) '\n", pdf_text(line)));
+        }
+        stream.push_str("ET");
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{stream}\nendstream",
+            stream.len()
+        ));
+    }
+
+    let mut pdf = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.push_str(&format!("{} 0 obj\n{object}\nendobj\n", index + 1));
+    }
+    let xref = pdf.len();
+    pdf.push_str(&format!(
+        "xref\n0 {}\n0000000000 65535 f \n",
+        objects.len() + 1
+    ));
+    for offset in offsets {
+        pdf.push_str(&format!("{offset:010} 00000 n \n"));
+    }
+    pdf.push_str(&format!(
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+        objects.len() + 1
+    ));
+    pdf
+}
+
+/// Escapes `text` as a PDF string in WinAnsi encoding. Thin-space digit
+/// grouping becomes plain spaces and superscript exponents are written
+/// after a caret, as in `1.2×10^-7`, since Courier has neither.
+fn pdf_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    let mut exponent = false;
+    for c in text.chars() {
+        let superscript = display::superscript_digit(c);
+        if superscript.is_some() && !exponent {
+            escaped.push('^');
+        }
+        exponent = superscript.is_some();
+        if let Some(digit) = superscript {
+            escaped.push(digit);
+            continue;
+        }
+        match c {
+            '(' | ')' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            ' '..='~' => escaped.push(c),
+            '−' => escaped.push('-'),
+            '≈' => escaped.push('~'),
+            '\u{2009}' | '\u{202F}' => escaped.push(' '),
+            '€' => escaped.push_str("\\200"),
+            '…' => escaped.push_str("\\205"),
+            // WinAnsi matches Latin-1 here, including × and ÷.
+            '\u{a0}'..='\u{ff}' => escaped.push_str(&format!("\\{:03o}", c as u32)),
+            _ => escaped.push('?'),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn owned(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(operator, operand)| (operator.to_string(), operand.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn entries_split_at_top_level_operators() {
+        assert_eq!(
+            entries("12 + 3.5*2 - 1"),
+            owned(&[("", "12"), ("+", "3.5"), ("×", "2"), ("−", "1")])
+        );
+        assert_eq!(
+            entries("(1+2)/sqrt(4+5)"),
+            owned(&[("", "(1+2)"), ("÷", "sqrt(4+5)")])
+        );
+        assert_eq!(entries("17 mod 5"), owned(&[("", "17"), ("mod", "5")]));
+    }
+
+    #[test]
+    fn entries_keep_unary_and_exponent_signs() {
+        assert_eq!(entries("-3 * -2"), owned(&[("", "-3"), ("×", "-2")]));
+        assert_eq!(
+            entries("1.2E-7+1e+3"),
+            owned(&[("", "1.2E-7"), ("+", "1e+3")])
+        );
+    }
+
+    #[test]
+    fn chained_results_are_not_repeated() {
+        let mut tape = Tape::default();
+        tape.record("2+3", "5", Kind::Total, false);
+        tape.record("5*4", "20", Kind::Subtotal, false);
+        let values = tape
+            .lines
+            .iter()
+            .map(|line| (line.operator.as_str(), line.value.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            [("", "2"), ("+", "3"), ("=", "5"), ("×", "4"), ("=", "20")]
+        );
+    }
+
+    #[test]
+    fn text_lines_align_values_and_markers() {
+        let mut tape = Tape::default();
+        tape.record("2+3", "5", Kind::Total, false);
+        tape.lines[0].note = String::from("rent");
+        let lines = tape.text_lines(false);
+        assert_eq!(lines[0], format!("{:>28}   rent", "2"));
+        assert_eq!(lines[1], format!("+   {:>24}", "3"));
+        assert_eq!(lines[2], format!("=   {:>24} T", "5"));
+        assert_eq!(lines[3], "");
+        assert!(lines[4].ends_with(": 5"));
+    }
+
+    #[test]
+    fn csv_quotes_fields_that_need_it() {
+        let mut tape = Tape::default();
+        tape.record("1,5+2", "3,5", Kind::Subtotal, true);
+        tape.lines[1].note = String::from("say \"hi\"");
+        assert_eq!(
+            tape.to_csv(),
+            "operator,value,kind,note\n\
+             ,\"1,5\",entry,\n\
+             +,2,entry,\"say \"\"hi\"\"\"\n\
+             =,\"3,5\",subtotal,\n"
+        );
+    }
+
+    #[test]
+    fn pdf_text_writes_grouping_and_exponents_in_ascii() {
+        assert_eq!(pdf_text("1\u{2009}234\u{2009}567"), "1 234 567");
+        assert_eq!(pdf_text("1.2×10⁻⁷"), "1.2\\32710^-7");
+        assert_eq!(pdf_text("−4.7\u{202F}µ"), "-4.7 \\265");
+        assert_eq!(pdf_text("(≈ 2)"), "\\(~ 2\\)");
+        assert_eq!(pdf_text("→"), "?");
+    }
+}
@@ -0,0 +1,187 @@
+use cosmic::{Element, widget};
+
+use crate::app::{
+    operations::{self, Calculator, EvaluateError},
+    tool::Action,
+};
+use crate::fl;
+
+const PRESETS: [&str; 4] = ["10", "15", "18", "20"];
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Bill(String),
+    TipPercent(String),
+    People(String),
+    RoundUp(bool),
+    Calculate,
+}
+
+/// What each person pays and what the bill adds up to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Split {
+    pub tip: f64,
+    pub total: f64,
+    pub per_person: f64,
+}
+
+/// Splits `bill` plus a `tip_percent` tip between `people`. Rounding up
+/// raises each share to the next whole unit, and the tip absorbs the
+/// difference.
+pub fn split(bill: f64, tip_percent: f64, people: u32, round_up: bool) -> Split {
+    let people = f64::from(people.max(1));
+    let mut per_person = bill * (1.0 + tip_percent / 100.0) / people;
+    if round_up {
+        // Tolerate float noise so an exact 25.000000000000004 stays 25.
+        per_person = (per_person - 1e-9).ceil();
+    }
+    let total = per_person * people;
+    Split {
+        tip: total - bill,
+        total,
+        per_person,
+    }
+}
+
+pub struct Tip {
+    bill: String,
+    tip_percent: String,
+    people: String,
+    round_up: bool,
+    split: Option<Split>,
+}
+
+impl Default for Tip {
+    fn default() -> Self {
+        Self {
+            bill: String::new(),
+            tip_percent: String::from("15"),
+            people: String::from("1"),
+            round_up: false,
+            split: None,
+        }
+    }
+}
+
+impl Tip {
+    pub fn update(&mut self, message: Message, decimal_comma: bool) -> Action<Message> {
+        match message {
+            Message::Bill(bill) => self.bill = bill,
+            Message::TipPercent(tip_percent) => self.tip_percent = tip_percent,
+            Message::People(people) => self.people = people,
+            Message::RoundUp(round_up) => self.round_up = round_up,
+            Message::Calculate => return self.calculate(decimal_comma),
+        }
+        Action::None
+    }
+
+    fn calculate(&mut self, decimal_comma: bool) -> Action<Message> {
+        let (Some(bill), Some(tip_percent), Ok(people)) = (
+            operations::parse_number(&self.bill, decimal_comma).filter(|bill| *bill >= 0.0),
+            operations::parse_number(&self.tip_percent, decimal_comma),
+            self.people.trim().parse::<u32>(),
+        ) else {
+            return Action::Error(EvaluateError::InvalidInput);
+        };
+        if people == 0 {
+            return Action::Error(EvaluateError::InvalidInput);
+        }
+
+        let split = split(bill, tip_percent, people, self.round_up);
+        self.split = Some(split);
+
+        let amount = |value: f64| operations::format_amount(value, decimal_comma);
+        // Written out in full, so the entry evaluates the same in either
+        // percent mode when it is reused.
+        let mut expression = format!(
+            "{}*(1+{}/100)/{people}",
+            amount(bill),
+            operations::number_literal(tip_percent, decimal_comma)
+        );
+        if self.round_up {
+            expression = format!("ceil({expression})");
+        }
+        Action::History(Calculator {
+            expression,
+            outcome: amount(split.per_person),
+            decimal_comma,
+            details: vec![
+                fl!("tip-amount", value = amount(split.tip)),
+                fl!("bill-total", value = amount(split.total)),
+                fl!("per-person", value = amount(split.per_person)),
+            ],
+            unrounded: None,
+        })
+    }
+
+    pub fn view(&self, decimal_comma: bool) -> Element<'_, Message> {
+        let spacing = cosmic::theme::active().cosmic().spacing;
+
+        let presets = PRESETS
+            .iter()
+            .fold(widget::row::with_capacity(PRESETS.len()), |row, preset| {
+                let button = if self.tip_percent.trim() == *preset {
+                    widget::button::suggested(format!("{preset}%"))
+                } else {
+                    widget::button::standard(format!("{preset}%"))
+                };
+                row.push(button.on_press(Message::TipPercent(preset.to_string())))
+            })
+            .spacing(spacing.space_xxs);
+
+        let inputs = widget::settings::section()
+            .add(widget::settings::item(
+                fl!("bill"),
+                widget::text_input("", &self.bill)
+                    .on_input(Message::Bill)
+                    .on_submit(|_| Message::Calculate),
+            ))
+            .add(widget::settings::item(
+                fl!("tip-percent"),
+                widget::text_input("%", &self.tip_percent)
+                    .on_input(Message::TipPercent)
+                    .on_submit(|_| Message::Calculate),
+            ))
+            .add(presets)
+            .add(widget::settings::item(
+                fl!("people"),
+                widget::text_input("1", &self.people)
+                    .on_input(Message::People)
+                    .on_submit(|_| Message::Calculate),
+            ))
+            .add(
+                widget::settings::item::builder(fl!("round-up"))
+                    .description(fl!("round-up-description"))
+                    .toggler(self.round_up, Message::RoundUp),
+            );
+
+        let mut column = vec![
+            inputs.into(),
+            widget::button::suggested(fl!("calculate"))
+                .on_press(Message::Calculate)
+                .into(),
+        ];
+        if let Some(split) = self.split {
+            let amount = |value: f64| operations::format_amount(value, decimal_comma);
+            column.push(
+                widget::settings::section()
+                    .title(fl!("result"))
+                    .add(widget::settings::item(
+                        fl!("tip"),
+                        widget::text::body(amount(split.tip)),
+                    ))
+                    .add(widget::settings::item(
+                        fl!("total"),
+                        widget::text::body(amount(split.total)),
+                    ))
+                    .add(widget::settings::item(
+                        fl!("per-person-label"),
+                        widget::text::heading(amount(split.per_person)),
+                    ))
+                    .into(),
+            );
+        }
+
+        widget::settings::view_column(column).into()
+    }
+}
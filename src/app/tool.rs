@@ -0,0 +1,71 @@
+use std::{
+    fs::File,
+    io::{self, Write},
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use cosmic::iced::Task;
+
+use crate::app::operations::{Calculator, EvaluateError};
+
+/// What a tool panel asks the application to do after handling a message.
+pub enum Action<M> {
+    None,
+    /// Run a task whose messages go back to the tool.
+    Task(Task<M>),
+    /// Store a calculation in the history.
+    History(Calculator),
+    /// Append a value to the calculator's expression.
+    Insert(String),
+    /// Copy text to the clipboard.
+    Copy(String),
+    /// Save text to a new file, named after the given stem and extension.
+    Export(&'static str, &'static str, String),
+    /// Report a failed evaluation.
+    Error(EvaluateError),
+}
+
+impl<M> From<Task<M>> for Action<M> {
+    fn from(task: Task<M>) -> Self {
+        Self::Task(task)
+    }
+}
+
+/// Writes `contents` to a new timestamped file in the user's documents
+/// folder. A numeric suffix keeps exports made within the same second apart.
+pub fn export(stem: &str, extension: &str, contents: &str) -> io::Result<PathBuf> {
+    let directory = dirs::document_dir()
+        .filter(|directory| directory.is_dir())
+        .or_else(dirs::home_dir)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no home directory"))?;
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    let mut suffix = 0;
+    loop {
+        let name = match suffix {
+            0 => format!("calculator-{stem}-{timestamp}.{extension}"),
+            _ => format!("calculator-{stem}-{timestamp}-{suffix}.{extension}"),
+        };
+        let path = directory.join(name);
+        match File::create_new(&path) {
+            Ok(mut file) => {
+                file.write_all(contents.as_bytes())?;
+                return Ok(path);
+            }
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => suffix += 1,
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Quotes a CSV field when it contains a separator, quote or line break.
+pub fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
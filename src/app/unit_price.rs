@@ -0,0 +1,319 @@
+use cosmic::{
+    Element,
+    iced::{Alignment, Length, Task},
+    widget,
+};
+
+use crate::app::{
+    operations::{self, EvaluateError},
+    tool::Action,
+};
+use crate::fl;
+
+const MAX_ITEMS: usize = 8;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Price(usize, String),
+    Quantity(usize, String),
+    AddItem,
+    RemoveItem(usize),
+    Per(String),
+    Compare,
+    Compared(u64, String, Vec<Option<f64>>),
+    Insert(usize),
+    Clear,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Item {
+    price: String,
+    /// An amount with its unit, such as `500 g` or `1.2 kg`.
+    quantity: String,
+    unit_price: Option<f64>,
+}
+
+pub struct UnitPrice {
+    items: Vec<Item>,
+    /// The unit prices are given per; empty uses the first item's unit.
+    per: String,
+    /// The unit of the last comparison.
+    compared_per: String,
+    generation: u64,
+}
+
+impl Default for UnitPrice {
+    fn default() -> Self {
+        Self {
+            items: vec![Item::default(), Item::default()],
+            per: String::new(),
+            compared_per: String::new(),
+            generation: 0,
+        }
+    }
+}
+
+impl UnitPrice {
+    pub fn update(&mut self, message: Message, decimal_comma: bool) -> Action<Message> {
+        match message {
+            Message::Price(index, price) => {
+                if let Some(item) = self.items.get_mut(index) {
+                    item.price = price;
+                    self.invalidate();
+                }
+            }
+            Message::Quantity(index, quantity) => {
+                if let Some(item) = self.items.get_mut(index) {
+                    item.quantity = quantity;
+                    self.invalidate();
+                }
+            }
+            Message::AddItem => {
+                if self.items.len() < MAX_ITEMS {
+                    self.items.push(Item::default());
+                    self.invalidate();
+                }
+            }
+            Message::RemoveItem(index) => {
+                if self.items.len() > 2 && index < self.items.len() {
+                    self.items.remove(index);
+                    self.invalidate();
+                }
+            }
+            Message::Per(per) => {
+                self.per = per;
+                self.invalidate();
+            }
+            Message::Compare => return self.compare(decimal_comma),
+            Message::Compared(generation, per, amounts) => {
+                if generation == self.generation {
+                    for (item, amount) in self.items.iter_mut().zip(amounts) {
+                        item.unit_price = amount
+                            .zip(operations::parse_number(&item.price, decimal_comma))
+                            .map(|(amount, price)| price / amount)
+                            .filter(|unit_price| unit_price.is_finite());
+                    }
+                    self.compared_per = per;
+                }
+            }
+            Message::Insert(index) => {
+                if let Some(unit_price) = self.items.get(index).and_then(|item| item.unit_price) {
+                    return Action::Insert(format_unit_price(unit_price, decimal_comma));
+                }
+            }
+            Message::Clear => *self = Self::default(),
+        }
+        Action::None
+    }
+
+    /// Drops unit prices that no longer match the items, along with any
+    /// comparison still running for them.
+    fn invalidate(&mut self) {
+        self.generation += 1;
+        for item in &mut self.items {
+            item.unit_price = None;
+        }
+    }
+
+    /// Converts every quantity to the common unit with qalc, so `500 g`
+    /// compares against `1.2 kg`. Quantities in an incompatible unit are
+    /// left without a unit price.
+    fn compare(&mut self, decimal_comma: bool) -> Action<Message> {
+        let per = match self.per.trim() {
+            "" => self
+                .items
+                .iter()
+                .map(|item| unit_of(&item.quantity))
+                .find(|unit| !unit.is_empty())
+                .unwrap_or_default()
+                .to_string(),
+            per => per.to_string(),
+        };
+        if self
+            .items
+            .iter()
+            .all(|item| item.price.trim().is_empty() || item.quantity.trim().is_empty())
+        {
+            return Action::Error(EvaluateError::InvalidInput);
+        }
+
+        // Each quantity divided by one common unit leaves a plain number.
+        let expressions = self
+            .items
+            .iter()
+            .map(|item| match (item.quantity.trim(), per.as_str()) {
+                ("", _) => String::new(),
+                (quantity, "") => quantity.to_string(),
+                (quantity, per) => format!("({quantity})/(1 {per})"),
+            })
+            .collect::<Vec<_>>();
+
+        self.generation += 1;
+        let generation = self.generation;
+        Task::perform(
+            async move {
+                // Batch only the filled rows; qalc can't answer an empty line.
+                let filled = expressions
+                    .iter()
+                    .filter(|expression| !expression.is_empty())
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let mut results = operations::evaluate_batch(&filled, decimal_comma, &[])
+                    .into_iter()
+                    .map(|result| {
+                        result
+                            .ok()
+                            .and_then(|amount| operations::parse_number(&amount, decimal_comma))
+                            .filter(|amount| *amount > 0.0)
+                    });
+                let amounts = expressions
+                    .iter()
+                    .map(|expression| {
+                        if expression.is_empty() {
+                            None
+                        } else {
+                            results.next().flatten()
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                (per, amounts)
+            },
+            move |(per, amounts)| Message::Compared(generation, per, amounts),
+        )
+        .into()
+    }
+
+    pub fn view(&self, decimal_comma: bool) -> Element<'_, Message> {
+        let spacing = cosmic::theme::active().cosmic().spacing;
+
+        let cheapest = self
+            .items
+            .iter()
+            .filter_map(|item| item.unit_price)
+            .min_by(f64::total_cmp);
+
+        let mut items = widget::settings::section().title(fl!("items"));
+        for (index, item) in self.items.iter().enumerate() {
+            let mut row = widget::row::with_capacity(4)
+                .push(
+                    widget::text_input(fl!("price"), &item.price)
+                        .on_input(move |price| Message::Price(index, price))
+                        .on_submit(|_| Message::Compare)
+                        .width(Length::FillPortion(2)),
+                )
+                .push(
+                    widget::text_input(fl!("quantity-placeholder"), &item.quantity)
+                        .on_input(move |quantity| Message::Quantity(index, quantity))
+                        .on_submit(|_| Message::Compare)
+                        .width(Length::FillPortion(2)),
+                )
+                .spacing(spacing.space_xxs)
+                .align_y(Alignment::Center);
+            if self.items.len() > 2 {
+                row = row.push(
+                    widget::button::icon(widget::icon::from_name("list-remove-symbolic"))
+                        .on_press(Message::RemoveItem(index)),
+                );
+            }
+            items = items.add(row);
+        }
+
+        let controls = widget::row::with_capacity(3)
+            .push(widget::button::suggested(fl!("compare")).on_press(Message::Compare))
+            .push(
+                widget::button::standard(fl!("add-item"))
+                    .on_press_maybe((self.items.len() < MAX_ITEMS).then_some(Message::AddItem)),
+            )
+            .push(widget::button::standard(fl!("clear")).on_press(Message::Clear))
+            .spacing(spacing.space_xxs);
+
+        let per = widget::settings::section().add(
+            widget::settings::item::builder(fl!("compare-per"))
+                .description(fl!("compare-per-description"))
+                .control(
+                    widget::text_input("kg", &self.per)
+                        .on_input(Message::Per)
+                        .on_submit(|_| Message::Compare)
+                        .width(Length::Fixed(100.0)),
+                ),
+        );
+
+        let mut column = vec![items.into(), per.into(), controls.into()];
+        if let Some(cheapest) = cheapest {
+            let mut results = widget::settings::section().title(fl!("unit-prices"));
+            for (index, item) in self.items.iter().enumerate() {
+                let label = format!("{} · {}", item.price.trim(), item.quantity.trim());
+                let Some(unit_price) = item.unit_price else {
+                    if !item.quantity.trim().is_empty() {
+                        results = results.add(widget::settings::item(
+                            label,
+                            widget::text::caption(fl!("incompatible-unit")),
+                        ));
+                    }
+                    continue;
+                };
+
+                let value = format!(
+                    "{} / {}",
+                    format_unit_price(unit_price, decimal_comma),
+                    self.compared_per
+                );
+                let (value, note) = if unit_price == cheapest {
+                    (widget::text::heading(value), fl!("cheapest"))
+                } else {
+                    let more = format!("{:.1}", (unit_price / cheapest - 1.0) * 100.0);
+                    let more = if decimal_comma {
+                        more.replace('.', ",")
+                    } else {
+                        more
+                    };
+                    (
+                        widget::text::body(value),
+                        fl!("more-expensive", value = more),
+                    )
+                };
+                results = results.add(widget::settings::item(
+                    label,
+                    widget::row::with_capacity(3)
+                        .push(
+                            widget::column::with_capacity(2)
+                                .push(value)
+                                .push(widget::text::caption(note)),
+                        )
+                        .push(
+                            widget::button::standard(fl!("insert"))
+                                .on_press(Message::Insert(index)),
+                        )
+                        .spacing(spacing.space_xxs)
+                        .align_y(Alignment::Center),
+                ));
+            }
+            column.push(results.into());
+        }
+
+        widget::settings::view_column(column)
+            .width(Length::Fill)
+            .into()
+    }
+}
+
+/// The unit of a quantity such as `500 g`, which is everything after its
+/// number.
+fn unit_of(quantity: &str) -> &str {
+    quantity
+        .trim()
+        .trim_start_matches(|c: char| c.is_ascii_digit() || matches!(c, '.' | ',' | ' '))
+        .trim()
+}
+
+/// Formats a unit price, keeping four decimals for small ones such as a
+/// price per gram.
+fn format_unit_price(value: f64, decimal_comma: bool) -> String {
+    let decimals = if value.abs() < 1.0 { 4 } else { 2 };
+    let text = format!("{value:.decimals$}");
+    if decimal_comma {
+        text.replace('.', ",")
+    } else {
+        text
+    }
+}